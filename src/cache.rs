@@ -0,0 +1,298 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Local};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::weather::{Astronomy, ForecastWeather, SearchLocation, Weather};
+
+const CACHE_DIR_NAME: &str = "rusty-weather";
+
+/// How responses are read from and written to the cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Serve fresh entries from the cache, fetch and store everything else
+    #[default]
+    Normal,
+    /// Neither read nor write the cache
+    Disabled,
+    /// Never touch the network, serve entries of any age
+    Offline,
+}
+
+/// On-disk cache for provider responses
+///
+/// Every entry is a JSON file in the cache directory, named after a hash of
+/// its key. The key is stored in the file as well, so hash collisions are
+/// detected. Errors while reading or writing are ignored, the cache is only
+/// an optimization.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: PathBuf,
+    mode: CacheMode,
+}
+
+/// An entry read from the cache
+#[derive(Debug)]
+pub struct CachedValue<T> {
+    pub data: T,
+    /// When the data was fetched from the provider
+    pub fetched_at: SystemTime,
+    /// Whether the entry is older than its time to live
+    pub expired: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry<T> {
+    key: String,
+    fetched_at: u64,
+    data: T,
+}
+
+/// Data that can be stored in the cache
+pub trait Cacheable: Serialize + DeserializeOwned {
+    /// Mark the data as served from an expired cache entry
+    ///
+    /// # Arguments
+    ///
+    /// * `fetched_at` - When the data was fetched, formatted for display
+    fn mark_stale(&mut self, fetched_at: String);
+}
+
+impl Cache {
+    /// Create a cache in the user's cache directory
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - How the cache is used
+    ///
+    /// # Returns
+    ///
+    /// The cache or `None` if no cache directory can be determined
+    pub fn new(mode: CacheMode) -> Option<Self> {
+        cache_dir().map(|dir| Cache::with_dir(dir, mode))
+    }
+
+    /// Create a cache in the given directory
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory to store the entries in
+    /// * `mode` - How the cache is used
+    pub fn with_dir(dir: PathBuf, mode: CacheMode) -> Self {
+        Cache { dir, mode }
+    }
+
+    /// Get how the cache is used
+    pub fn mode(&self) -> CacheMode {
+        self.mode
+    }
+
+    /// Read an entry from the cache
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the entry
+    /// * `ttl` - How long the entry is considered fresh
+    ///
+    /// # Returns
+    ///
+    /// The entry, expired or not, or `None` if there is no readable entry
+    pub fn get<T: DeserializeOwned>(&self, key: &str, ttl: Duration) -> Option<CachedValue<T>> {
+        let content = fs::read_to_string(self.path(key)).ok()?;
+        let entry: CacheEntry<T> = serde_json::from_str(&content).ok()?;
+        if entry.key != key {
+            return None;
+        }
+
+        let fetched_at = UNIX_EPOCH + Duration::from_secs(entry.fetched_at);
+        let age = SystemTime::now()
+            .duration_since(fetched_at)
+            .unwrap_or_default();
+        Some(CachedValue {
+            data: entry.data,
+            fetched_at,
+            expired: age > ttl,
+        })
+    }
+
+    /// Write an entry to the cache, replacing an older one
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the entry
+    /// * `data` - The data to store
+    pub fn put<T: Serialize>(&self, key: &str, data: &T) {
+        let fetched_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let entry = CacheEntry {
+            key: key.to_string(),
+            fetched_at,
+            data,
+        };
+        let Ok(content) = serde_json::to_string(&entry) else {
+            return;
+        };
+        if fs::create_dir_all(&self.dir).is_ok() {
+            // Write to a temporary file first so readers never see half an entry
+            let path = self.path(key);
+            let tmp = path.with_extension("tmp");
+            if fs::write(&tmp, content).is_ok() {
+                fs::rename(&tmp, &path).ok();
+            }
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.json", fnv1a(key)))
+    }
+}
+
+impl<T: Cacheable> CachedValue<T> {
+    /// Take the data, marked as stale if the entry is expired
+    pub fn into_data(self) -> T {
+        let mut data = self.data;
+        if self.expired {
+            let fetched_at: DateTime<Local> = self.fetched_at.into();
+            data.mark_stale(fetched_at.format("%Y-%m-%d %H:%M").to_string());
+        }
+        data
+    }
+}
+
+impl Cacheable for Weather {
+    fn mark_stale(&mut self, fetched_at: String) {
+        self.stale_since = Some(fetched_at);
+    }
+}
+
+impl Cacheable for ForecastWeather {
+    fn mark_stale(&mut self, fetched_at: String) {
+        self.stale_since = Some(fetched_at);
+    }
+}
+
+impl Cacheable for Astronomy {
+    fn mark_stale(&mut self, fetched_at: String) {
+        self.stale_since = Some(fetched_at);
+    }
+}
+
+impl Cacheable for Vec<SearchLocation> {
+    fn mark_stale(&mut self, _fetched_at: String) {}
+}
+
+/// Get the cache directory following the XDG base directory specification
+///
+/// Uses `$XDG_CACHE_HOME`, then `~/.cache` and `%LOCALAPPDATA%` on Windows.
+fn cache_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .or_else(|| std::env::var_os("LOCALAPPDATA").map(PathBuf::from))?;
+    Some(base.join(CACHE_DIR_NAME))
+}
+
+/// 64-bit FNV-1a hash, stable across Rust versions unlike `DefaultHasher`
+fn fnv1a(value: &str) -> u64 {
+    value.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: Duration = Duration::from_secs(60 * 60);
+
+    fn temp_cache(name: &str) -> Cache {
+        let dir = std::env::temp_dir().join(format!(
+            "rusty-weather-cache-{}-{}",
+            name,
+            std::process::id()
+        ));
+        fs::remove_dir_all(&dir).ok();
+        Cache::with_dir(dir, CacheMode::Normal)
+    }
+
+    /// Store an entry as if it was fetched some time ago
+    fn put_aged(cache: &Cache, key: &str, data: &Weather, age: Duration) {
+        let fetched_at = SystemTime::now() - age;
+        let entry = CacheEntry {
+            key: key.to_string(),
+            fetched_at: fetched_at.duration_since(UNIX_EPOCH).unwrap().as_secs(),
+            data,
+        };
+        fs::create_dir_all(&cache.dir).unwrap();
+        fs::write(cache.path(key), serde_json::to_string(&entry).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn stored_entries_are_read_back() {
+        let cache = temp_cache("roundtrip");
+        let mut weather = Weather::new();
+        weather.location.name = "Berlin".to_string();
+        cache.put("current|berlin", &weather);
+
+        let cached = cache.get::<Weather>("current|berlin", HOUR).unwrap();
+        assert_eq!(cached.data.location.name, "Berlin");
+        assert!(!cached.expired);
+        assert!(cache.get::<Weather>("current|paris", HOUR).is_none());
+    }
+
+    #[test]
+    fn entries_expire_after_their_ttl() {
+        let cache = temp_cache("ttl");
+        put_aged(&cache, "forecast|berlin", &Weather::new(), 2 * HOUR);
+
+        assert!(
+            !cache
+                .get::<Weather>("forecast|berlin", 3 * HOUR)
+                .unwrap()
+                .expired
+        );
+        let cached = cache.get::<Weather>("forecast|berlin", HOUR).unwrap();
+        assert!(cached.expired);
+        // Expired data is still served, but marked as stale
+        assert!(cached.into_data().stale_since.is_some());
+    }
+
+    #[test]
+    fn fresh_entries_are_not_marked_stale() {
+        let cache = temp_cache("fresh");
+        cache.put("current|berlin", &Weather::new());
+
+        let cached = cache.get::<Weather>("current|berlin", HOUR).unwrap();
+        assert!(cached.into_data().stale_since.is_none());
+    }
+
+    #[test]
+    fn entries_with_another_key_are_ignored() {
+        let cache = temp_cache("collision");
+        put_aged(&cache, "current|berlin", &Weather::new(), Duration::ZERO);
+        // Pretend another key hashes to the same file
+        let content = fs::read_to_string(cache.path("current|berlin")).unwrap();
+        fs::write(
+            cache.path("current|paris"),
+            content.replace("current|berlin", "current|rome"),
+        )
+        .unwrap();
+
+        assert!(cache.get::<Weather>("current|paris", HOUR).is_none());
+    }
+
+    #[test]
+    fn keys_are_hashed_to_separate_files() {
+        let cache = temp_cache("paths");
+        assert_ne!(cache.path("current|berlin"), cache.path("current|berlin "));
+        assert_ne!(cache.path("a|b"), cache.path("b|a"));
+        assert_eq!(fnv1a(""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a("a"), 0xaf63dc4c8601ec8c);
+    }
+}
@@ -0,0 +1,296 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Proxy, Response, StatusCode, Url};
+
+use crate::error::WeatherError;
+
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// The most retries a request may be configured with
+pub const MAX_RETRIES: u32 = 10;
+
+/// The longest delay before a retry, also for a server's `Retry-After`
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Query parameters whose values must never show up in errors or logs
+const SECRET_PARAMS: [&str; 4] = ["key", "apikey", "api_key", "token"];
+
+/// Settings for the shared HTTP client
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    /// Maximum time to establish a connection
+    pub connect_timeout: Duration,
+    /// Maximum time to wait for data from the server
+    pub read_timeout: Duration,
+    /// How often a failed request is retried, at most [`MAX_RETRIES`]
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for every further retry up to
+    /// 30 seconds
+    pub backoff: Duration,
+    /// Base URL that replaces the provider's default endpoint
+    pub base_url: Option<String>,
+    /// Proxy URL used for all requests
+    pub proxy: Option<String>,
+    /// The User-Agent header sent with every request
+    pub user_agent: String,
+}
+
+/// HTTP client shared by all providers
+///
+/// Wraps a `reqwest::Client` with timeouts, proxy settings and retries with
+/// exponential backoff for server errors and rate limiting. Cloning is cheap,
+/// all clones share the same connection pool.
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    client: reqwest::Client,
+    config: HttpConfig,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        HttpConfig {
+            connect_timeout: Duration::from_secs(10),
+            read_timeout: Duration::from_secs(30),
+            max_retries: 3,
+            backoff: Duration::from_millis(500),
+            base_url: None,
+            proxy: None,
+            user_agent: USER_AGENT.to_string(),
+        }
+    }
+}
+
+impl HttpClient {
+    /// Create a new client from the given settings
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The settings for the client
+    ///
+    /// # Errors
+    ///
+    /// If the proxy URL is invalid or the client cannot be built
+    pub fn new(config: HttpConfig) -> Result<Self, WeatherError> {
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(config.connect_timeout)
+            .read_timeout(config.read_timeout)
+            .user_agent(config.user_agent.as_str());
+        if let Some(proxy) = &config.proxy {
+            builder = builder.proxy(Proxy::all(proxy.as_str())?);
+        }
+
+        Ok(HttpClient {
+            client: builder.build()?,
+            config,
+        })
+    }
+
+    /// Get the base URL override, if one is configured
+    pub fn base_url(&self) -> Option<&str> {
+        self.config
+            .base_url
+            .as_deref()
+            .map(|url| url.trim_end_matches('/'))
+    }
+
+    /// Send a GET request, retrying on transient failures
+    ///
+    /// Connection errors, timeouts, server errors (5xx) and rate limiting
+    /// (429) are retried up to `max_retries` times with exponential backoff,
+    /// or after the delay of the server's `Retry-After` header on 429 and
+    /// 503. The last response is returned as is, the caller checks its status.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to request
+    /// * `params` - The query parameters, encoded by the client
+    ///
+    /// # Errors
+    ///
+    /// If the request still fails after all retries. Secret query parameters
+    /// are redacted from the URL in the error.
+    pub async fn get(
+        &self,
+        url: &str,
+        params: &[(&str, String)],
+    ) -> Result<Response, WeatherError> {
+        let mut attempt = 0;
+        loop {
+            let result = self.client.get(url).query(params).send().await;
+            let retry = match &result {
+                Ok(resp) => {
+                    resp.status().is_server_error()
+                        || resp.status() == StatusCode::TOO_MANY_REQUESTS
+                }
+                Err(e) => e.is_connect() || e.is_timeout(),
+            };
+            if !retry || attempt >= self.config.max_retries.min(MAX_RETRIES) {
+                return result.map_err(|mut e| {
+                    if let Some(url) = e.url_mut() {
+                        redact_url(url);
+                    }
+                    e.into()
+                });
+            }
+
+            let retry_after = result
+                .as_ref()
+                .ok()
+                .filter(|resp| {
+                    matches!(
+                        resp.status(),
+                        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+                    )
+                })
+                .and_then(|resp| resp.headers().get(RETRY_AFTER)?.to_str().ok())
+                .and_then(|value| parse_retry_after(value, Utc::now()));
+            tokio::time::sleep(retry_delay(self.config.backoff, attempt, retry_after)).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Get the delay before a retry
+///
+/// # Arguments
+///
+/// * `backoff` - The delay before the first retry
+/// * `attempt` - The number of the failed attempt, starting at 0
+/// * `retry_after` - The delay the server asked for, if any
+///
+/// # Returns
+///
+/// The delay the server asked for or the exponential backoff, at most
+/// [`MAX_BACKOFF`]
+fn retry_delay(backoff: Duration, attempt: u32, retry_after: Option<Duration>) -> Duration {
+    retry_after
+        .unwrap_or_else(|| backoff.saturating_mul(2u32.saturating_pow(attempt.min(16))))
+        .min(MAX_BACKOFF)
+}
+
+/// Parse a `Retry-After` header, either seconds or an HTTP date
+///
+/// # Arguments
+///
+/// * `value` - The value of the header
+/// * `now` - The current time, to turn a date into a delay
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&Utc) - now)
+        .to_std()
+        .ok()
+        .or(Some(Duration::ZERO))
+}
+
+/// Replace the values of secret query parameters like the API key
+///
+/// Use this before a URL ends up in an error message or a log.
+///
+/// # Arguments
+///
+/// * `url` - The URL to redact in place
+pub fn redact_url(url: &mut Url) {
+    if url.query().is_none() {
+        return;
+    }
+
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(name, value)| {
+            if SECRET_PARAMS.contains(&name.to_lowercase().as_str()) {
+                (name.into_owned(), "REDACTED".to_string())
+            } else {
+                (name.into_owned(), value.into_owned())
+            }
+        })
+        .collect();
+    url.query_pairs_mut().clear().extend_pairs(pairs);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_and_is_capped() {
+        let backoff = Duration::from_millis(500);
+        assert_eq!(retry_delay(backoff, 0, None), Duration::from_millis(500));
+        assert_eq!(retry_delay(backoff, 2, None), Duration::from_secs(2));
+        assert_eq!(retry_delay(backoff, 10, None), MAX_BACKOFF);
+        assert_eq!(retry_delay(backoff, u32::MAX, None), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn retry_after_wins_but_is_capped() {
+        let backoff = Duration::from_millis(500);
+        let retry_after = Some(Duration::from_secs(5));
+        assert_eq!(retry_delay(backoff, 0, retry_after), Duration::from_secs(5));
+        let retry_after = Some(Duration::from_secs(3600));
+        assert_eq!(retry_delay(backoff, 0, retry_after), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn retry_after_accepts_seconds_and_dates() {
+        let now = DateTime::parse_from_rfc3339("2026-10-18T07:28:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Sun, 18 Oct 2026 07:28:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Sun, 18 Oct 2026 07:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn redact_url_hides_secret_parameters() {
+        let mut url =
+            Url::parse("https://api.example.com/v1/current.json?key=secret123&q=Berlin&TOKEN=abc")
+                .unwrap();
+        redact_url(&mut url);
+        let text = url.to_string();
+        assert!(!text.contains("secret123"));
+        assert!(!text.contains("abc"));
+        assert!(text.contains("key=REDACTED"));
+        assert!(text.contains("q=Berlin"));
+    }
+
+    #[test]
+    fn request_errors_never_contain_the_api_key() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let client = HttpClient::new(HttpConfig {
+            max_retries: 0,
+            ..Default::default()
+        })
+        .unwrap();
+        // Nothing listens on port 9 of localhost, so the request fails
+        let error = runtime
+            .block_on(client.get(
+                "http://127.0.0.1:9/current.json",
+                &[
+                    ("key", "secret123".to_string()),
+                    ("q", "Berlin".to_string()),
+                ],
+            ))
+            .unwrap_err();
+        let message = error.to_string();
+        assert!(!message.contains("secret123"), "{}", message);
+        assert!(message.contains("REDACTED"), "{}", message);
+    }
+}
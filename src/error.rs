@@ -0,0 +1,153 @@
+use thiserror::Error;
+
+use crate::utils::translate;
+
+/// Errors that can occur while fetching weather data
+#[derive(Debug, Error)]
+pub enum WeatherError {
+    #[error("No API key configured, set the WEATHER_API environment variable")]
+    MissingApiKey,
+    #[error("Network error: {0}")]
+    Network(#[from] reqwest::Error),
+    #[error("Unexpected HTTP status: {0}")]
+    HttpStatus(u16),
+    #[error("Provider error {code}: {message}")]
+    Provider { code: i64, message: String },
+    #[error("Failed to decode the response: {0}")]
+    Decode(String),
+    #[error("No matching location found for '{0}'")]
+    UnknownLocation(String),
+    #[error("The API quota has been exceeded")]
+    QuotaExceeded,
+    #[error("The provider does not support the location query '{0}'")]
+    UnsupportedQuery(String),
+    #[error("The provider does not support {0}")]
+    UnsupportedFeature(String),
+    #[error("No cached data available for this request while offline")]
+    NotCached,
+}
+
+impl From<serde_json::Error> for WeatherError {
+    fn from(error: serde_json::Error) -> Self {
+        WeatherError::Decode(error.to_string())
+    }
+}
+
+impl WeatherError {
+    /// Get the translated message for the error
+    ///
+    /// Falls back to the English description if the translation is missing.
+    pub fn localized(&self) -> String {
+        let translated = match self {
+            WeatherError::MissingApiKey => translate("error.missing_api_key", &[]),
+            WeatherError::Network(e) => {
+                translate("error.network", &[("details", e.to_string().as_str())])
+            }
+            WeatherError::HttpStatus(status) => translate(
+                "error.http_status",
+                &[("status", status.to_string().as_str())],
+            ),
+            WeatherError::Provider { code, message } => {
+                // Prefer the translated explanation of known provider codes
+                let message = translate(&format!("error.provider_codes.{}", code), &[])
+                    .unwrap_or_else(|| message.clone());
+                translate(
+                    "error.provider",
+                    &[("code", code.to_string().as_str()), ("message", &message)],
+                )
+            }
+            WeatherError::Decode(details) => translate("error.decode", &[("details", details)]),
+            WeatherError::UnknownLocation(location) => {
+                translate("error.unknown_location", &[("location", location)])
+            }
+            WeatherError::QuotaExceeded => translate("error.quota_exceeded", &[]),
+            WeatherError::UnsupportedQuery(query) => {
+                translate("error.unsupported_query", &[("query", query)])
+            }
+            WeatherError::UnsupportedFeature(feature) => {
+                let feature = translate(&format!("error.features.{}", feature), &[])
+                    .unwrap_or_else(|| feature.clone());
+                translate("error.unsupported_feature", &[("feature", &feature)])
+            }
+            WeatherError::NotCached => translate("error.not_cached", &[]),
+        };
+        translated.unwrap_or_else(|| self.to_string())
+    }
+
+    /// Whether the error is caused by the network or an unavailable provider
+    ///
+    /// Cached data may be served instead when such an error occurs.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            WeatherError::Network(_) | WeatherError::QuotaExceeded => true,
+            WeatherError::HttpStatus(status) => *status >= 500,
+            _ => false,
+        }
+    }
+
+    /// Get the process exit code for the error
+    ///
+    /// Every kind of error has its own exit code so scripts can react to it.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            WeatherError::MissingApiKey => 3,
+            WeatherError::Network(_) => 4,
+            WeatherError::HttpStatus(_) => 5,
+            WeatherError::Provider { .. } => 6,
+            WeatherError::Decode(_) => 7,
+            WeatherError::UnknownLocation(_) => 8,
+            WeatherError::QuotaExceeded => 9,
+            WeatherError::UnsupportedQuery(_) => 10,
+            WeatherError::UnsupportedFeature(_) => 11,
+            WeatherError::NotCached => 12,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network_error() -> WeatherError {
+        reqwest::Client::new()
+            .get("not a url")
+            .build()
+            .unwrap_err()
+            .into()
+    }
+
+    #[test]
+    fn every_error_has_its_own_exit_code() {
+        let errors = [
+            (WeatherError::MissingApiKey, 3),
+            (network_error(), 4),
+            (WeatherError::HttpStatus(502), 5),
+            (
+                WeatherError::Provider {
+                    code: 9999,
+                    message: "Internal application error.".to_string(),
+                },
+                6,
+            ),
+            (WeatherError::Decode("expected value".to_string()), 7),
+            (WeatherError::UnknownLocation("Atlantis".to_string()), 8),
+            (WeatherError::QuotaExceeded, 9),
+            (WeatherError::UnsupportedQuery("FRA".to_string()), 10),
+            (WeatherError::UnsupportedFeature("alerts".to_string()), 11),
+            (WeatherError::NotCached, 12),
+        ];
+        for (error, code) in &errors {
+            assert_eq!(error.exit_code(), *code, "{:?}", error);
+        }
+    }
+
+    #[test]
+    fn only_network_and_server_errors_are_transient() {
+        assert!(network_error().is_transient());
+        assert!(WeatherError::QuotaExceeded.is_transient());
+        assert!(WeatherError::HttpStatus(503).is_transient());
+        assert!(!WeatherError::HttpStatus(404).is_transient());
+        assert!(!WeatherError::MissingApiKey.is_transient());
+        assert!(!WeatherError::UnknownLocation("Atlantis".to_string()).is_transient());
+    }
+}
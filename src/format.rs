@@ -0,0 +1,225 @@
+use std::fmt::Write;
+use std::iter;
+use std::sync::RwLock;
+
+use chrono::format::{Fixed, Item, StrftimeItems};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+
+use crate::utils::{ClockFormat, FormatConfig, translate};
+use crate::weather::parse_clock_time;
+
+/// The formats used if neither the language nor the configuration sets one
+const DEFAULT_DATE: &str = "%Y-%m-%d";
+const DEFAULT_DAY: &str = "%A, %Y-%m-%d";
+
+/// Formats from the configuration that override those of the language
+static OVERRIDES: RwLock<FormatConfig> = RwLock::new(FormatConfig {
+    decimal_separator: None,
+    date: None,
+    day: None,
+    clock: None,
+});
+
+/// Override the formats of the active language
+///
+/// # Arguments
+///
+/// * `config` - The `[format]` section of the configuration
+pub fn set_format_overrides(config: FormatConfig) {
+    if let Ok(mut overrides) = OVERRIDES.write() {
+        *overrides = config;
+    }
+}
+
+/// Check whether a date pattern is valid
+///
+/// # Arguments
+///
+/// * `pattern` - A `strftime` pattern like `%d.%m.%Y`
+pub fn is_valid_date_pattern(pattern: &str) -> bool {
+    StrftimeItems::new(pattern).parse().is_ok()
+}
+
+/// Format a decimal number with the decimal separator of the language
+///
+/// # Arguments
+///
+/// * `value` - The number to format
+pub fn format_number(value: f64) -> String {
+    let separator = overrides(|config| config.decimal_separator)
+        .or_else(|| translate("format.decimal_separator", &[])?.chars().next())
+        .unwrap_or('.');
+    let text = value.to_string();
+    if separator == '.' {
+        text
+    } else {
+        text.replace('.', &separator.to_string())
+    }
+}
+
+/// Format a date like `18.10.2026`
+pub fn format_date(date: NaiveDate) -> String {
+    let pattern = overrides(|config| config.date.clone()).or_else(|| pattern("format.date"));
+    format_with(
+        date,
+        pattern.as_deref().unwrap_or(DEFAULT_DATE),
+        DEFAULT_DATE,
+    )
+}
+
+/// Format a date with its weekday like `Sonntag, 18.10.2026`
+pub fn format_day(date: NaiveDate) -> String {
+    let pattern = overrides(|config| config.day.clone()).or_else(|| pattern("format.day"));
+    format_with(date, pattern.as_deref().unwrap_or(DEFAULT_DAY), DEFAULT_DAY)
+}
+
+/// Format a time of day on the 12 or 24-hour clock
+pub fn format_time(time: NaiveTime) -> String {
+    match clock_format() {
+        ClockFormat::H24 => time.format("%H:%M").to_string(),
+        ClockFormat::H12 => time.format("%I:%M %p").to_string(),
+    }
+}
+
+/// Format a date and time like `18.10.2026 14:30`
+pub fn format_datetime(datetime: NaiveDateTime) -> String {
+    format!(
+        "{} {}",
+        format_date(datetime.date()),
+        format_time(datetime.time())
+    )
+}
+
+/// Format a date given as `YYYY-MM-DD`, other values are kept
+pub fn format_date_str(value: &str) -> String {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map(format_date)
+        .unwrap_or_else(|_| value.to_string())
+}
+
+/// Format a date given as `YYYY-MM-DD` with its weekday, other values are
+/// kept
+pub fn format_day_str(value: &str) -> String {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map(format_day)
+        .unwrap_or_else(|_| value.to_string())
+}
+
+/// Format a time given as `07:31 AM` or `07:31`, other values like
+/// `No sunrise` are kept
+pub fn format_time_str(value: &str) -> String {
+    parse_clock_time(value)
+        .map(format_time)
+        .unwrap_or_else(|| value.to_string())
+}
+
+/// Format a date and time given as `YYYY-MM-DD HH:MM` or RFC 3339, other
+/// values are kept
+///
+/// Times with an offset are shown in that offset, not converted.
+pub fn format_datetime_str(value: &str) -> String {
+    let value = value.trim();
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+        .ok()
+        .or_else(|| {
+            DateTime::parse_from_rfc3339(value)
+                .ok()
+                .map(|datetime| datetime.naive_local())
+        })
+        .map(format_datetime)
+        .unwrap_or_else(|| value.to_string())
+}
+
+/// Get whether times are shown on the 12 or 24-hour clock
+pub fn clock_format() -> ClockFormat {
+    overrides(|config| config.clock)
+        .or_else(|| match translate("format.clock", &[])?.as_str() {
+            "12h" => Some(ClockFormat::H12),
+            "24h" => Some(ClockFormat::H24),
+            _ => None,
+        })
+        .unwrap_or(ClockFormat::H24)
+}
+
+fn overrides<T>(get: impl Fn(&FormatConfig) -> Option<T>) -> Option<T> {
+    OVERRIDES.read().ok().and_then(|config| get(&config))
+}
+
+/// Get a date pattern of the language if it is valid
+fn pattern(key: &str) -> Option<String> {
+    translate(key, &[]).filter(|pattern| is_valid_date_pattern(pattern))
+}
+
+/// Format a date with a pattern, with translated weekday names
+///
+/// The pattern is formatted item by item so `%A` and `%a` can be replaced
+/// by the translated names, chrono only knows the English ones.
+fn format_with(date: NaiveDate, pattern: &str, fallback: &str) -> String {
+    let weekday = weekday_key(date.weekday());
+    let mut text = String::new();
+    for item in StrftimeItems::new(pattern) {
+        let name = match item {
+            Item::Fixed(Fixed::LongWeekdayName) => {
+                translate(&format!("format.weekdays.{}", weekday), &[])
+            }
+            Item::Fixed(Fixed::ShortWeekdayName) => {
+                translate(&format!("format.weekdays_short.{}", weekday), &[])
+            }
+            _ => None,
+        };
+        let written = match name {
+            Some(name) => write!(text, "{}", name),
+            None => write!(text, "{}", date.format_with_items(iter::once(item))),
+        };
+        if written.is_err() {
+            // An invalid pattern from a translation file or the configuration
+            return date.format(fallback).to_string();
+        }
+    }
+    text
+}
+
+fn weekday_key(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "monday",
+        Weekday::Tue => "tuesday",
+        Weekday::Wed => "wednesday",
+        Weekday::Thu => "thursday",
+        Weekday::Fri => "friday",
+        Weekday::Sat => "saturday",
+        Weekday::Sun => "sunday",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sunday() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+    }
+
+    #[test]
+    fn weekday_names_without_a_translation_are_english() {
+        assert_eq!(
+            format_with(sunday(), "%A, %d.%m.%Y", DEFAULT_DATE),
+            "Sunday, 18.10.2026"
+        );
+        assert_eq!(format_with(sunday(), "%a %d", DEFAULT_DATE), "Sun 18");
+    }
+
+    #[test]
+    fn escaped_percent_signs_are_kept() {
+        assert_eq!(
+            format_with(sunday(), "%%A %%a %A", DEFAULT_DATE),
+            "%A %a Sunday"
+        );
+        assert_eq!(format_with(sunday(), "%d%%", DEFAULT_DATE), "18%");
+    }
+
+    #[test]
+    fn invalid_pattern_uses_the_fallback() {
+        assert!(!is_valid_date_pattern("%Q"));
+        assert_eq!(format_with(sunday(), "%d %Q", DEFAULT_DATE), "2026-10-18");
+    }
+}
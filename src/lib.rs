@@ -1,13 +1,37 @@
+mod cache;
+mod client;
+mod error;
+mod format;
+mod location;
+mod output;
+mod provider;
+mod units;
 mod utils;
 mod weather;
 
 pub mod prelude {
+    pub use crate::cache::*;
+    pub use crate::client::*;
+    pub use crate::error::*;
+    pub use crate::format::*;
+    pub use crate::location::*;
+    pub use crate::output::*;
+    pub use crate::provider::*;
+    pub use crate::units::*;
     pub use crate::utils::*;
-    pub use crate::weather::{ForecastWeather, Weather};
-    pub use crate::{Cli, LanguageCommand, WeatherCommand};
+    pub use crate::weather::*;
+    pub use crate::{Cli, ConfigCommand, LanguageCommand, LocationCommand, WeatherCommand};
 }
 
+use chrono::{NaiveDate, NaiveTime};
 use clap::Parser;
+use clap::builder::PossibleValuesParser;
+use client::MAX_RETRIES;
+use location::{LocationArgs, parse_location_name};
+use output::{OutputFormat, parse_delimiter};
+use provider::{MAX_FORECAST_DAYS, ProviderKind};
+use units::{DistanceUnit, PrecipitationUnit, PressureUnit, TemperatureUnit, Units, WindUnit};
+use utils::CONFIG_KEYS;
 
 #[derive(Parser)]
 #[clap(
@@ -18,27 +42,198 @@ use clap::Parser;
 pub struct Cli {
     #[clap(subcommand)]
     pub commands: WeatherCommand,
+    #[clap(
+        long,
+        global = true,
+        value_enum,
+        help = "The weather provider to use (defaults to the configured provider)"
+    )]
+    pub provider: Option<ProviderKind>,
+    #[clap(
+        long,
+        global = true,
+        help = "Send all requests to this base URL instead of the provider's endpoint"
+    )]
+    pub base_url: Option<String>,
+    #[clap(
+        long,
+        global = true,
+        help = "The proxy URL to send all requests through"
+    )]
+    pub proxy: Option<String>,
+    #[clap(long, global = true, help = "The read timeout for requests in seconds")]
+    pub timeout: Option<u64>,
+    #[clap(
+        long,
+        global = true,
+        value_parser = clap::value_parser!(u32).range(..=MAX_RETRIES as i64),
+        help = "How often failed requests are retried (at most 10)"
+    )]
+    pub retries: Option<u32>,
+    #[clap(
+        long,
+        global = true,
+        value_enum,
+        help = "The format to print the weather data in [default: table]"
+    )]
+    pub output: Option<OutputFormat>,
+    #[clap(
+        long,
+        global = true,
+        value_enum,
+        help = "The unit system of the table output [default: metric]"
+    )]
+    pub units: Option<Units>,
+    #[clap(
+        long,
+        global = true,
+        value_enum,
+        help = "The unit of temperatures, overrides --units"
+    )]
+    pub temperature_unit: Option<TemperatureUnit>,
+    #[clap(
+        long,
+        global = true,
+        value_enum,
+        help = "The unit of wind speeds, overrides --units"
+    )]
+    pub wind_unit: Option<WindUnit>,
+    #[clap(
+        long,
+        global = true,
+        value_enum,
+        help = "The unit of air pressure, overrides --units"
+    )]
+    pub pressure_unit: Option<PressureUnit>,
+    #[clap(
+        long,
+        global = true,
+        value_enum,
+        help = "The unit of precipitation, overrides --units"
+    )]
+    pub precipitation_unit: Option<PrecipitationUnit>,
+    #[clap(
+        long,
+        global = true,
+        value_enum,
+        help = "The unit of distances, overrides --units"
+    )]
+    pub distance_unit: Option<DistanceUnit>,
+    #[clap(long, global = true, help = "Indent the JSON output")]
+    pub pretty: bool,
+    #[clap(
+        long,
+        global = true,
+        value_parser = parse_delimiter,
+        help = "The field delimiter for CSV and TSV output"
+    )]
+    pub delimiter: Option<char>,
+    #[clap(
+        long,
+        global = true,
+        help = "Always fetch fresh data and do not store it in the cache"
+    )]
+    pub no_cache: bool,
+    #[clap(
+        long,
+        global = true,
+        conflicts_with = "no_cache",
+        help = "Only use cached data, even if it is outdated"
+    )]
+    pub offline: bool,
 }
 
 #[derive(Parser)]
 pub enum WeatherCommand {
     #[clap(about = "Get the current weather for a city")]
     Current {
-        #[clap(short, long, help = "The city to get the weather for")]
-        city: String,
+        #[clap(flatten)]
+        location: LocationArgs,
+        #[clap(long, help = "Include air quality data")]
+        air_quality: bool,
     },
     #[clap(about = "Get the forcast weather for a city and days")]
     Forecast {
-        #[clap(short, long, help = "The city to get the weather for")]
-        city: String,
-        #[clap(short, long, help = "The days to get the weather for")]
-        days: usize,
+        #[clap(flatten)]
+        location: LocationArgs,
+        #[clap(
+            short,
+            long,
+            value_parser = clap::value_parser!(u8).range(1..=i64::from(MAX_FORECAST_DAYS)),
+            help = "The days to get the weather for (1 to 14)"
+        )]
+        days: u8,
+        #[clap(long, help = "Include air quality data")]
+        air_quality: bool,
+        #[clap(long, help = "Include official weather alerts")]
+        alerts: bool,
+        #[clap(long, help = "Show the forecast hour by hour instead of per day")]
+        hourly: bool,
+        #[clap(
+            long,
+            value_parser = parse_time,
+            help = "Only show hours from this time of day on (HH:MM)"
+        )]
+        from: Option<NaiveTime>,
+        #[clap(
+            long,
+            value_parser = parse_time,
+            help = "Only show hours up to this time of day (HH:MM)"
+        )]
+        to: Option<NaiveTime>,
+    },
+    #[clap(about = "Get the observed weather of past days for a city")]
+    History {
+        #[clap(flatten)]
+        location: LocationArgs,
+        #[clap(long, help = "The (first) day to get the weather for (YYYY-MM-DD)")]
+        date: NaiveDate,
+        #[clap(long, help = "The last day of a date range (YYYY-MM-DD)")]
+        end_date: Option<NaiveDate>,
+        #[clap(long, help = "Show the weather hour by hour instead of per day")]
+        hourly: bool,
+    },
+    #[clap(about = "Get the sun and moon data for a city and day")]
+    Astronomy {
+        #[clap(flatten)]
+        location: LocationArgs,
+        #[clap(
+            long,
+            help = "The day to get the data for (YYYY-MM-DD, defaults to today)"
+        )]
+        date: Option<NaiveDate>,
+    },
+    #[clap(about = "Search for locations matching a name")]
+    Search {
+        #[clap(short, long, help = "The (partial) location name to search for")]
+        query: String,
+        #[clap(
+            short,
+            long,
+            help = "Show the current weather for the result with this number"
+        )]
+        pick: Option<usize>,
+    },
+    #[clap(about = "Get the official weather alerts for a city")]
+    Alerts {
+        #[clap(flatten)]
+        location: LocationArgs,
     },
     #[clap(about = "Configure the language for the weather app")]
     Language {
         #[clap(subcommand)]
         commands: LanguageCommand,
-    }
+    },
+    #[clap(about = "Show and change the settings of the weather app")]
+    Config {
+        #[clap(subcommand)]
+        commands: ConfigCommand,
+    },
+    #[clap(about = "Manage named locations, usable as --city @name")]
+    Location {
+        #[clap(subcommand)]
+        commands: LocationCommand,
+    },
 }
 
 #[derive(Parser)]
@@ -52,4 +247,88 @@ pub enum LanguageCommand {
     List,
     #[clap(about = "Get the current language")]
     Get,
+    #[clap(about = "Compare the translations with English and report missing or wrong keys")]
+    Check,
+}
+
+#[derive(Parser)]
+pub enum ConfigCommand {
+    #[clap(about = "Show the value of a setting")]
+    Get {
+        #[clap(value_parser = PossibleValuesParser::new(CONFIG_KEYS), help = "The setting to show")]
+        key: String,
+    },
+    #[clap(about = "Change a setting")]
+    Set {
+        #[clap(value_parser = PossibleValuesParser::new(CONFIG_KEYS), help = "The setting to change")]
+        key: String,
+        #[clap(help = "The new value")]
+        value: String,
+    },
+    #[clap(about = "Remove a setting to use the default again")]
+    Unset {
+        #[clap(value_parser = PossibleValuesParser::new(CONFIG_KEYS), help = "The setting to remove")]
+        key: String,
+    },
+    #[clap(about = "List all settings of the config file")]
+    List,
+    #[clap(about = "Show the path of the config file")]
+    Path,
+    #[clap(about = "Open the config file in $VISUAL or $EDITOR")]
+    Edit,
+}
+
+#[derive(Parser)]
+pub enum LocationCommand {
+    #[clap(about = "Resolve a location and save it under a name")]
+    Add {
+        #[clap(value_parser = parse_location_name, help = "The name to save the location as, e.g. office")]
+        name: String,
+        #[clap(flatten)]
+        location: LocationArgs,
+        #[clap(long, help = "Replace a location saved under the same name")]
+        force: bool,
+    },
+    #[clap(about = "Remove a saved location")]
+    Remove {
+        #[clap(value_parser = parse_location_name, help = "The name of the location")]
+        name: String,
+    },
+    #[clap(about = "List the saved locations")]
+    List,
+    #[clap(about = "Rename a saved location")]
+    Rename {
+        #[clap(value_parser = parse_location_name, help = "The current name of the location")]
+        name: String,
+        #[clap(value_parser = parse_location_name, help = "The new name of the location")]
+        new_name: String,
+    },
+}
+
+/// Parse a time of day given as `HH:MM` or just `HH`
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(&format!("{}:00", value), "%H:%M"))
+        .map_err(|_| format!("invalid time '{}', expected HH:MM", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forecast_days(days: &str) -> Result<u8, clap::Error> {
+        let cli = Cli::try_parse_from(["rusty-weather", "forecast", "--days", days])?;
+        match cli.commands {
+            WeatherCommand::Forecast { days, .. } => Ok(days),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn forecast_days_are_limited() {
+        assert_eq!(forecast_days("1").unwrap(), 1);
+        assert_eq!(forecast_days("14").unwrap(), MAX_FORECAST_DAYS);
+        assert!(forecast_days("0").is_err());
+        assert!(forecast_days("15").is_err());
+    }
 }
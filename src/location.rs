@@ -0,0 +1,266 @@
+use std::fmt;
+
+use clap::{ArgGroup, Args};
+use serde::{Deserialize, Serialize};
+
+/// Marks the name of a saved location in `--city`, e.g. `@office`
+pub const SAVED_LOCATION_PREFIX: char = '@';
+
+/// A location to get the weather for
+#[derive(Debug, Clone, PartialEq)]
+pub enum LocationQuery {
+    /// A city or place name, e.g. `Frankfurt am Main`
+    City(String),
+    /// Latitude and longitude in decimal degrees
+    Coordinates { lat: f64, lon: f64 },
+    /// A postcode or zip code, e.g. `10115` or `SW1`
+    Zip(String),
+    /// An IATA airport code, e.g. `FRA`
+    Iata(String),
+    /// A METAR station code, e.g. `EDDF`
+    Metar(String),
+    /// The location of the caller's IP address
+    AutoIp,
+}
+
+/// A named location saved in the configuration
+///
+/// The coordinates are resolved once when the location is saved, so the
+/// name always refers to the same place.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedLocation {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub region: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub country: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl SavedLocation {
+    /// Get the query for the saved coordinates
+    pub fn query(&self) -> LocationQuery {
+        LocationQuery::Coordinates {
+            lat: self.lat,
+            lon: self.lon,
+        }
+    }
+
+    /// Get the name, region and country joined for display
+    pub fn display_name(&self) -> String {
+        [&self.name, &self.region, &self.country]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Command line arguments to select a location
+///
+/// At most one kind of location can be given, `--lat` and `--lon` only
+/// together. Without a location the default from the configuration is used.
+#[derive(Debug, Args)]
+#[group(skip)]
+#[command(group(ArgGroup::new("location").multiple(false)))]
+pub struct LocationArgs {
+    #[clap(
+        short,
+        long,
+        group = "location",
+        help = "The city to get the weather for, or @name for a saved location"
+    )]
+    pub city: Option<String>,
+    #[clap(
+        long,
+        group = "location",
+        requires = "lon",
+        allow_negative_numbers = true,
+        help = "The latitude to get the weather for"
+    )]
+    pub lat: Option<f64>,
+    #[clap(
+        long,
+        requires = "lat",
+        allow_negative_numbers = true,
+        help = "The longitude to get the weather for"
+    )]
+    pub lon: Option<f64>,
+    #[clap(
+        long,
+        group = "location",
+        help = "The postcode or zip code to get the weather for"
+    )]
+    pub zip: Option<String>,
+    #[clap(
+        long,
+        group = "location",
+        help = "The IATA airport code to get the weather for"
+    )]
+    pub iata: Option<String>,
+    #[clap(
+        long,
+        group = "location",
+        help = "The METAR station code to get the weather for"
+    )]
+    pub metar: Option<String>,
+    #[clap(
+        long,
+        group = "location",
+        help = "Get the weather for the location of your IP address"
+    )]
+    pub auto_ip: bool,
+}
+
+impl LocationArgs {
+    /// Convert the arguments into a location query
+    ///
+    /// # Returns
+    ///
+    /// The selected location or `None` if no location was given
+    pub fn to_query(&self) -> Option<LocationQuery> {
+        if let Some(city) = &self.city {
+            Some(LocationQuery::City(city.trim().to_string()))
+        } else if let (Some(lat), Some(lon)) = (self.lat, self.lon) {
+            Some(LocationQuery::Coordinates { lat, lon })
+        } else if let Some(zip) = &self.zip {
+            Some(LocationQuery::Zip(zip.trim().to_string()))
+        } else if let Some(iata) = &self.iata {
+            Some(LocationQuery::Iata(iata.trim().to_uppercase()))
+        } else if let Some(metar) = &self.metar {
+            Some(LocationQuery::Metar(metar.trim().to_uppercase()))
+        } else if self.auto_ip {
+            Some(LocationQuery::AutoIp)
+        } else {
+            None
+        }
+    }
+}
+
+impl LocationQuery {
+    /// Get the name of the saved location the query refers to
+    ///
+    /// # Returns
+    ///
+    /// The name without the `@` prefix or `None` if the query is not a
+    /// saved location
+    pub fn saved_name(&self) -> Option<&str> {
+        match self {
+            LocationQuery::City(city) => city.strip_prefix(SAVED_LOCATION_PREFIX),
+            _ => None,
+        }
+    }
+}
+
+/// Parse the name of a saved location
+///
+/// Names consist of letters, digits, `-` and `_`, a leading `@` is ignored.
+pub fn parse_location_name(value: &str) -> Result<String, String> {
+    let name = value.trim().trim_start_matches(SAVED_LOCATION_PREFIX);
+    if !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        Ok(name.to_string())
+    } else {
+        Err(format!(
+            "invalid name '{}', use letters, digits, '-' and '_'",
+            value
+        ))
+    }
+}
+
+impl fmt::Display for LocationQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LocationQuery::City(city) => write!(f, "{}", city),
+            LocationQuery::Coordinates { lat, lon } => write!(f, "{},{}", lat, lon),
+            LocationQuery::Zip(zip) => write!(f, "{}", zip),
+            LocationQuery::Iata(iata) => write!(f, "iata:{}", iata),
+            LocationQuery::Metar(metar) => write!(f, "metar:{}", metar),
+            LocationQuery::AutoIp => write!(f, "auto:ip"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    #[derive(Parser)]
+    struct TestCli {
+        #[clap(flatten)]
+        location: LocationArgs,
+    }
+
+    fn query(args: &[&str]) -> Option<LocationQuery> {
+        let cli = TestCli::try_parse_from([&["test"], args].concat()).unwrap();
+        cli.location.to_query()
+    }
+
+    #[test]
+    fn every_kind_of_location_becomes_a_query() {
+        assert_eq!(
+            query(&["--city", " São Paulo "]),
+            Some(LocationQuery::City("São Paulo".to_string()))
+        );
+        assert_eq!(
+            query(&["--lat", "-23.55", "--lon", "-46.63"]),
+            Some(LocationQuery::Coordinates {
+                lat: -23.55,
+                lon: -46.63
+            })
+        );
+        assert_eq!(
+            query(&["--zip", "SW1 "]),
+            Some(LocationQuery::Zip("SW1".to_string()))
+        );
+        assert_eq!(
+            query(&["--iata", "fra"]),
+            Some(LocationQuery::Iata("FRA".to_string()))
+        );
+        assert_eq!(
+            query(&["--metar", "eddf"]),
+            Some(LocationQuery::Metar("EDDF".to_string()))
+        );
+        assert_eq!(query(&["--auto-ip"]), Some(LocationQuery::AutoIp));
+        assert_eq!(query(&[]), None);
+    }
+
+    #[test]
+    fn only_one_location_is_accepted() {
+        assert!(TestCli::try_parse_from(["test", "--city", "Berlin", "--zip", "10115"]).is_err());
+        assert!(TestCli::try_parse_from(["test", "--lat", "52.5"]).is_err());
+    }
+
+    #[test]
+    fn queries_are_displayed_like_the_api_expects_them() {
+        let display = |args: &[&str]| query(args).unwrap().to_string();
+        assert_eq!(display(&["--city", "A & B"]), "A & B");
+        assert_eq!(
+            display(&["--lat", "52.52", "--lon", "13.405"]),
+            "52.52,13.405"
+        );
+        assert_eq!(display(&["--zip", "10115"]), "10115");
+        assert_eq!(display(&["--iata", "fra"]), "iata:FRA");
+        assert_eq!(display(&["--metar", "EDDF"]), "metar:EDDF");
+        assert_eq!(display(&["--auto-ip"]), "auto:ip");
+    }
+
+    #[test]
+    fn saved_locations_are_recognised() {
+        assert_eq!(
+            query(&["--city", "@office"]).unwrap().saved_name(),
+            Some("office")
+        );
+        assert_eq!(query(&["--city", "office"]).unwrap().saved_name(), None);
+        assert_eq!(parse_location_name("@home"), Ok("home".to_string()));
+        assert!(parse_location_name("my office").is_err());
+        assert!(parse_location_name("@").is_err());
+    }
+}
@@ -1,67 +1,282 @@
+use std::io::{IsTerminal, Write};
 use std::path::Path;
+use std::time::Duration;
 
-use clap::Parser;
+use chrono::{Local, NaiveDate};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
 use lingua_i18n_rs::prelude::Lingua;
 use rusty_weather::prelude::*;
 
+/// The longest date range of the history command, every day is one request
+const MAX_HISTORY_DAYS: i64 = 31;
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    Lingua::new("languages").init()?;
+    load_embedded_languages()?;
 
-    let language = Lingua::load_lang_from_config(Path::new("config.ini"), "language").expect("Failed to load language");
+    let migrated = migrate_legacy_config().unwrap_or_else(|e| exit_with_config_error(e));
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => exit_with_config_error(e),
+    };
+    if let Some(dir) = config.languages_dir() {
+        for error in load_language_dir(&dir) {
+            eprintln!(
+                "{}",
+                t(
+                    "language.load_error",
+                    &[("message", error.to_string().as_str())]
+                )
+            );
+        }
+    }
 
-    Lingua::set_language(language.as_str()).unwrap();
+    Lingua::set_language(&select_language(config.language.as_deref())).ok();
+    set_format_overrides(config.format.clone());
+    if let (Some(legacy), Ok(path)) = (migrated, config_path()) {
+        eprintln!(
+            "{}",
+            t(
+                "config.migrated",
+                &[
+                    ("from", legacy.display().to_string().as_str()),
+                    ("to", path.display().to_string().as_str()),
+                ]
+            )
+        );
+    }
 
     let args = Cli::parse();
+    let output = args.output.or(config.output).unwrap_or_default();
+    set_units(unit_settings(&args, &config));
+    let client = match HttpClient::new(http_config(&args, &config)) {
+        Ok(client) => client,
+        Err(e) => exit_with_error(e),
+    };
+    let cache_mode = if args.offline {
+        CacheMode::Offline
+    } else if args.no_cache || config.cache.enabled == Some(false) {
+        CacheMode::Disabled
+    } else {
+        CacheMode::Normal
+    };
+    let provider = Provider::new(select_provider(args.provider, &config), client)
+        .with_cache(Cache::new(cache_mode))
+        .with_offline(args.offline)
+        .with_api_key(config.api_key.clone());
     match args.commands {
-        WeatherCommand::Current { city } => {
+        WeatherCommand::Current {
+            ref location,
+            air_quality,
+        } => {
             let lang = Lingua::get_language().unwrap();
-            let weather = Weather::new();
-            let result = weather.get_current_weather(city, lang).await;
+            let (query, saved) = location_query(location, &config);
+            let options = FetchOptions {
+                air_quality,
+                ..Default::default()
+            };
+            let result = provider.current(&query, &lang, &options).await;
             match result {
-                Ok(weather) => {
-                    weather.print_current_weather();
+                Ok(mut weather) => {
+                    keep_saved_name(&mut weather.location, saved);
+                    print_current(&weather, output, &args)?
                 }
-                Err(e) => {
-                    eprintln!("Error: {}", e);
+                Err(e) => exit_with_error(e),
+            }
+        }
+        WeatherCommand::Forecast {
+            location,
+            days,
+            air_quality,
+            alerts,
+            hourly,
+            from,
+            to,
+        } => {
+            let lang = Lingua::get_language().unwrap();
+            let (query, saved) = location_query(&location, &config);
+            let options = FetchOptions {
+                air_quality,
+                alerts,
+            };
+            let result = provider
+                .forecast(&query, usize::from(days), &lang, &options)
+                .await;
+            match result {
+                Ok(mut weather) => {
+                    keep_saved_name(&mut weather.location, saved);
+                    weather.filter_hours(from, to);
+                    match output {
+                        OutputFormat::Table if hourly => weather.print_hourly_forecast(),
+                        OutputFormat::Table => weather.print_forecast_weather(),
+                        OutputFormat::Json => println!("{}", weather.to_json(args.pretty)?),
+                        OutputFormat::Csv | OutputFormat::Tsv => print!(
+                            "{}",
+                            weather.to_csv(output.delimiter(args.delimiter), hourly)?
+                        ),
+                    }
                 }
+                Err(e) => exit_with_error(e),
             }
         }
-        WeatherCommand::Forecast { city, days } => {
+        WeatherCommand::History {
+            location,
+            date,
+            end_date,
+            hourly,
+        } => {
+            let end_date =
+                history_end(date, end_date, Local::now().date_naive()).unwrap_or_else(|message| {
+                    Cli::command()
+                        .error(ErrorKind::ValueValidation, message)
+                        .exit()
+                });
             let lang = Lingua::get_language().unwrap();
-            let weather = ForecastWeather::new();
-            let result = weather.get_forecast_weather(days, city, lang).await;
+            let (query, saved) = location_query(&location, &config);
+            let result = provider.history_range(&query, date, end_date, &lang).await;
             match result {
-                Ok(weather) => {
-                    weather.print_forecast_weather();
+                Ok(mut weather) => {
+                    keep_saved_name(&mut weather.location, saved);
+                    match output {
+                        OutputFormat::Table if hourly => weather.print_hourly_history(),
+                        OutputFormat::Table => weather.print_history_weather(),
+                        OutputFormat::Json => println!("{}", weather.to_json(args.pretty)?),
+                        OutputFormat::Csv | OutputFormat::Tsv => print!(
+                            "{}",
+                            weather.to_csv(output.delimiter(args.delimiter), hourly)?
+                        ),
+                    }
                 }
-                Err(e) => {
-                    eprintln!("Error: {}", e);
+                Err(e) => exit_with_error(e),
+            }
+        }
+        WeatherCommand::Astronomy { location, date } => {
+            let lang = Lingua::get_language().unwrap();
+            let (query, saved) = location_query(&location, &config);
+            let result = provider
+                .astronomy_with_previous_day(&query, date, &lang)
+                .await;
+            match result {
+                Ok(mut astronomy) => {
+                    keep_saved_name(&mut astronomy.location, saved);
+                    match output {
+                        OutputFormat::Table => astronomy.print_astronomy(),
+                        OutputFormat::Json => println!("{}", astronomy.to_json(args.pretty)?),
+                        OutputFormat::Csv | OutputFormat::Tsv => {
+                            print!("{}", astronomy.to_csv(output.delimiter(args.delimiter))?)
+                        }
+                    }
                 }
+                Err(e) => exit_with_error(e),
             }
         }
-        WeatherCommand::Language { commands } => match commands {
-            LanguageCommand::Set { lang } => {
-                if let Ok(is_set_lang) = Lingua::set_language(lang.as_str()) {
-                    if is_set_lang {
-                        save_language(lang.as_str()).unwrap();
-                        println!(
+        WeatherCommand::Search { ref query, pick } => {
+            let lang = Lingua::get_language().unwrap();
+            let locations = match provider.search(query, &lang).await {
+                Ok(locations) if locations.is_empty() => {
+                    exit_with_error(WeatherError::UnknownLocation(query.clone()))
+                }
+                Ok(locations) => locations,
+                Err(e) => exit_with_error(e),
+            };
+            let index = match pick {
+                Some(index) if index == 0 || index > locations.len() => Cli::command()
+                    .error(
+                        ErrorKind::ValueValidation,
+                        t(
+                            "search.invalid_index",
+                            &[("count", locations.len().to_string().as_str())],
+                        ),
+                    )
+                    .exit(),
+                Some(index) => Some(index - 1),
+                None => {
+                    match output {
+                        OutputFormat::Table => print_locations(query, &locations),
+                        OutputFormat::Json => {
+                            println!("{}", locations_to_json(query, &locations, args.pretty)?)
+                        }
+                        OutputFormat::Csv | OutputFormat::Tsv => print!(
                             "{}",
-                            Lingua::t("language.set_language_ok", &[("lang", lang.as_str())])
-                                .unwrap()
-                        )
+                            locations_to_csv(&locations, output.delimiter(args.delimiter))?
+                        ),
+                    }
+                    let interactive =
+                        std::io::stdin().is_terminal() && std::io::stdout().is_terminal();
+                    if output == OutputFormat::Table && interactive {
+                        prompt_location(locations.len())
+                    } else {
+                        None
+                    }
+                }
+            };
+
+            if let Some(index) = index {
+                let location = &locations[index];
+                let query = LocationQuery::Coordinates {
+                    lat: location.lat,
+                    lon: location.lon,
+                };
+                let result = provider
+                    .current(&query, &lang, &FetchOptions::default())
+                    .await;
+                match result {
+                    Ok(mut weather) => {
+                        // Keep the name the user picked instead of the resolved one
+                        weather.location.name = location.name.clone();
+                        weather.location.region = location.region.clone();
+                        weather.location.country = location.country.clone();
+                        print_current(&weather, output, &args)?
+                    }
+                    Err(e) => exit_with_error(e),
+                }
+            }
+        }
+        WeatherCommand::Alerts { location } => {
+            let lang = Lingua::get_language().unwrap();
+            let (query, saved) = location_query(&location, &config);
+            let options = FetchOptions {
+                alerts: true,
+                ..Default::default()
+            };
+            let result = provider.forecast(&query, 1, &lang, &options).await;
+            match result {
+                Ok(mut weather) => {
+                    keep_saved_name(&mut weather.location, saved);
+                    match output {
+                        OutputFormat::Table => weather.print_alerts(),
+                        OutputFormat::Json => {
+                            println!("{}", weather.alerts_to_json(args.pretty)?)
+                        }
+                        OutputFormat::Csv | OutputFormat::Tsv => print!(
+                            "{}",
+                            weather.alerts_to_csv(output.delimiter(args.delimiter))?
+                        ),
                     }
-                } else {
+                }
+                Err(e) => exit_with_error(e),
+            }
+        }
+        WeatherCommand::Language { commands } => match commands {
+            LanguageCommand::Set { lang } => match match_language(&lang) {
+                Some(lang) => {
+                    Lingua::set_language(&lang).ok();
+                    save_language(&lang);
                     println!(
                         "{}",
-                        Lingua::t("language.set_language_error", &[("lang", lang.as_str())])
-                            .unwrap()
+                        t("language.set_language_ok", &[("lang", lang.as_str())])
                     )
                 }
-            }
+                None => println!(
+                    "{}",
+                    t("language.set_language_error", &[("lang", lang.as_str())])
+                ),
+            },
             LanguageCommand::List => {
-                let languages = Lingua::get_languages().unwrap();
-                println!("{}", Lingua::t("language.list_languages", &[]).unwrap());
+                let mut languages = Lingua::get_languages().unwrap();
+                languages.sort();
+                println!("{}", t("language.list_languages", &[]));
                 for lang in languages {
                     println!("{}", lang);
                 }
@@ -70,11 +285,623 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 let lang = Lingua::get_language().unwrap();
                 println!(
                     "{}",
-                    Lingua::t("language.current_language", &[("lang", lang.as_str())]).unwrap()
+                    t("language.current_language", &[("lang", lang.as_str())])
                 );
             }
+            LanguageCommand::Check => {
+                let reports = check_languages();
+                for report in &reports {
+                    print_language_report(report);
+                }
+                if reports.iter().any(|report| report.problem_count() > 0) {
+                    std::process::exit(1);
+                }
+            }
         },
+        WeatherCommand::Config { commands } => config_command(commands),
+        WeatherCommand::Location { commands } => location_command(commands, &provider).await,
     }
 
     Ok(())
 }
+
+/// Print the current weather in the selected output format
+fn print_current(
+    weather: &Weather,
+    output: OutputFormat,
+    args: &Cli,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match output {
+        OutputFormat::Table => weather.print_current_weather(),
+        OutputFormat::Json => println!("{}", weather.to_json(args.pretty)?),
+        OutputFormat::Csv | OutputFormat::Tsv => {
+            print!("{}", weather.to_csv(output.delimiter(args.delimiter))?)
+        }
+    }
+    Ok(())
+}
+
+/// Ask the user to pick one of the listed locations
+///
+/// # Arguments
+///
+/// * `count` - The number of listed locations
+///
+/// # Returns
+///
+/// The zero-based index of the picked location or `None` if the user
+/// cancelled with an empty line
+/// Check the dates of the history command
+///
+/// # Arguments
+///
+/// * `date` - The first day
+/// * `end_date` - The last day, the first day if not given
+/// * `today` - The current date
+///
+/// # Returns
+///
+/// The last day, or the translated error if the range is reversed, longer than
+/// `MAX_HISTORY_DAYS` or reaches into the future
+fn history_end(
+    date: NaiveDate,
+    end_date: Option<NaiveDate>,
+    today: NaiveDate,
+) -> Result<NaiveDate, String> {
+    let end_date = end_date.unwrap_or(date);
+    if end_date < date || (end_date - date).num_days() >= MAX_HISTORY_DAYS {
+        return Err(t(
+            "history.invalid_range",
+            &[("max_days", MAX_HISTORY_DAYS.to_string().as_str())],
+        ));
+    }
+    if end_date > today {
+        return Err(t("history.future_date", &[]));
+    }
+    Ok(end_date)
+}
+
+fn prompt_location(count: usize) -> Option<usize> {
+    let mut line = String::new();
+    loop {
+        print!(
+            "{} ",
+            t("search.prompt", &[("count", count.to_string().as_str())])
+        );
+        std::io::stdout().flush().ok()?;
+        line.clear();
+        if std::io::stdin().read_line(&mut line).ok()? == 0 || line.trim().is_empty() {
+            return None;
+        }
+        match line.trim().parse::<usize>() {
+            Ok(index) if (1..=count).contains(&index) => return Some(index - 1),
+            _ => println!(
+                "{}",
+                t(
+                    "search.invalid_index",
+                    &[("count", count.to_string().as_str())]
+                )
+            ),
+        }
+    }
+}
+
+/// Build the HTTP settings from the CLI flags and the configuration
+fn http_config(args: &Cli, config: &Config) -> HttpConfig {
+    let network = &config.network;
+    let mut http = HttpConfig {
+        base_url: args.base_url.clone().or_else(|| network.base_url.clone()),
+        proxy: args.proxy.clone().or_else(|| network.proxy.clone()),
+        ..Default::default()
+    };
+    if let Some(timeout) = args.timeout.or(network.timeout) {
+        http.read_timeout = Duration::from_secs(timeout);
+    }
+    if let Some(retries) = args.retries.or(network.retries) {
+        http.max_retries = retries;
+    }
+    http
+}
+
+/// Select the units of every quantity from the CLI flags and the
+/// configuration, a single quantity's unit wins over the unit system
+fn unit_settings(args: &Cli, config: &Config) -> UnitSettings {
+    let mut units = args.units.or(config.units).unwrap_or_default().settings();
+    if let Some(unit) = args.temperature_unit.or(config.temperature_unit) {
+        units.temperature = unit;
+    }
+    if let Some(unit) = args.wind_unit.or(config.wind_unit) {
+        units.wind = unit;
+    }
+    if let Some(unit) = args.pressure_unit.or(config.pressure_unit) {
+        units.pressure = unit;
+    }
+    if let Some(unit) = args.precipitation_unit.or(config.precipitation_unit) {
+        units.precipitation = unit;
+    }
+    if let Some(unit) = args.distance_unit.or(config.distance_unit) {
+        units.distance = unit;
+    }
+    units
+}
+
+/// Select the provider from the CLI flag or the configuration
+///
+/// Without an explicit choice weatherapi.com is used when an API key is
+/// available and Open-Meteo otherwise.
+fn select_provider(flag: Option<ProviderKind>, config: &Config) -> ProviderKind {
+    flag.or(config.provider)
+        .unwrap_or(if config.api_key.is_some() {
+            ProviderKind::WeatherApi
+        } else {
+            ProviderKind::OpenMeteo
+        })
+}
+
+/// Print the result of the translation check for one language
+fn print_language_report(report: &LanguageReport) {
+    let lang = report.language.as_str();
+    let count = report.problem_count();
+    if count == 0 {
+        println!("{}", t("language.check.complete", &[("lang", lang)]));
+        return;
+    }
+
+    println!(
+        "{}",
+        t(
+            "language.check.problems",
+            &[("lang", lang), ("count", count.to_string().as_str())]
+        )
+    );
+    for key in &report.missing {
+        println!("  {}", t("language.check.missing", &[("key", key)]));
+    }
+    for key in &report.extra {
+        println!("  {}", t("language.check.extra", &[("key", key)]));
+    }
+    for mismatch in &report.placeholder_mismatches {
+        println!(
+            "  {}",
+            t(
+                "language.check.placeholders",
+                &[
+                    ("key", mismatch.key.as_str()),
+                    ("expected", mismatch.expected.join(", ").as_str()),
+                    ("found", mismatch.found.join(", ").as_str()),
+                ]
+            )
+        );
+    }
+}
+
+/// Run a config subcommand on the configuration file
+///
+/// Settings from the environment are neither shown nor written.
+fn config_command(command: ConfigCommand) {
+    let mut config = Config::load_file().unwrap_or_else(|e| exit_with_config_error(e));
+    match command {
+        ConfigCommand::Get { key } => match config.get(&key) {
+            Ok(Some(value)) => println!("{}", value),
+            Ok(None) => {
+                eprintln!("{}", t("config.not_set", &[("key", key.as_str())]));
+                std::process::exit(1);
+            }
+            Err(e) => exit_with_config_error(e),
+        },
+        ConfigCommand::Set { key, mut value } => {
+            if key == "language" {
+                match match_language(&value) {
+                    Some(lang) => value = lang,
+                    None => Cli::command()
+                        .error(
+                            ErrorKind::ValueValidation,
+                            t("language.set_language_error", &[("lang", value.as_str())]),
+                        )
+                        .exit(),
+                }
+            }
+            match config.set(&key, &value) {
+                Ok(()) => {}
+                Err(ConfigError::InvalidValue { key, value }) => {
+                    let mut message = t(
+                        "config.invalid_value",
+                        &[("key", key.as_str()), ("value", value.as_str())],
+                    );
+                    if let Some(values) = possible_values(&key) {
+                        message.push_str(&format!(
+                            " ({})",
+                            t(
+                                "config.possible_values",
+                                &[("values", values.join(", ").as_str())]
+                            )
+                        ));
+                    }
+                    Cli::command()
+                        .error(ErrorKind::ValueValidation, message)
+                        .exit();
+                }
+                Err(e) => exit_with_config_error(e),
+            }
+            if let Err(e) = config.save() {
+                exit_with_config_error(e);
+            }
+            let shown = config.get(&key).ok().flatten().unwrap_or(value);
+            let shown = display_value(&key, &shown);
+            println!(
+                "{}",
+                t(
+                    "config.set_ok",
+                    &[("key", key.as_str()), ("value", shown.as_str())]
+                )
+            );
+        }
+        ConfigCommand::Unset { key } => {
+            let was_set = config
+                .unset(&key)
+                .unwrap_or_else(|e| exit_with_config_error(e));
+            if was_set && let Err(e) = config.save() {
+                exit_with_config_error(e);
+            }
+            let message = if was_set {
+                "config.unset_ok"
+            } else {
+                "config.not_set"
+            };
+            println!("{}", t(message, &[("key", key.as_str())]));
+        }
+        ConfigCommand::List => {
+            let settings: Vec<(&str, String)> = CONFIG_KEYS
+                .iter()
+                .filter_map(|key| {
+                    let value = config.get(key).ok().flatten()?;
+                    Some((*key, display_value(key, &value)))
+                })
+                .collect();
+            if settings.is_empty() {
+                println!("{}", t("config.empty", &[]));
+            }
+            for (key, value) in settings {
+                println!("{} = {}", key, value);
+            }
+        }
+        ConfigCommand::Path => match config_path() {
+            Ok(path) => println!("{}", path.display()),
+            Err(e) => exit_with_config_error(e),
+        },
+        ConfigCommand::Edit => {
+            let path = config_path().unwrap_or_else(|e| exit_with_config_error(e));
+            if !path.exists()
+                && let Err(e) = config.save()
+            {
+                exit_with_config_error(e);
+            }
+            edit_file(&path);
+            // Report mistakes right away instead of on the next run
+            if let Err(e) = Config::load() {
+                exit_with_config_error(e);
+            }
+        }
+    }
+}
+
+/// Run a location subcommand on the saved locations
+async fn location_command(command: LocationCommand, provider: &Provider) {
+    let mut config = Config::load_file().unwrap_or_else(|e| exit_with_config_error(e));
+    // Printed once the changes are saved
+    let messages = match command {
+        LocationCommand::Add {
+            name,
+            location,
+            force,
+        } => {
+            if !force && config.locations.contains_key(&name) {
+                Cli::command()
+                    .error(
+                        ErrorKind::ValueValidation,
+                        t("location.exists", &[("name", name.as_str())]),
+                    )
+                    .exit();
+            }
+            let Some(query) = location.to_query() else {
+                Cli::command()
+                    .error(
+                        ErrorKind::MissingRequiredArgument,
+                        t("location.missing_add", &[]),
+                    )
+                    .exit();
+            };
+            let saved = match saved_location(&query, &config) {
+                // Copy a saved location without resolving it again
+                Some(saved) => saved.clone(),
+                None => {
+                    let lang = Lingua::get_language().unwrap();
+                    let weather = match provider
+                        .current(&query, &lang, &FetchOptions::default())
+                        .await
+                    {
+                        Ok(weather) => weather,
+                        Err(e) => exit_with_error(e),
+                    };
+                    SavedLocation {
+                        name: weather.location.name,
+                        region: weather.location.region,
+                        country: weather.location.country,
+                        lat: weather.location.lat,
+                        lon: weather.location.lon,
+                    }
+                }
+            };
+            let message = t(
+                "location.saved",
+                &[
+                    ("name", name.as_str()),
+                    ("location", saved.display_name().as_str()),
+                    ("lat", format_number(saved.lat).as_str()),
+                    ("lon", format_number(saved.lon).as_str()),
+                ],
+            );
+            config.locations.insert(name, saved);
+            vec![message]
+        }
+        LocationCommand::Remove { name } => {
+            if config.locations.remove(&name).is_none() {
+                exit_with_error(WeatherError::UnknownLocation(format!("@{}", name)));
+            }
+            let mut messages = vec![t("location.removed", &[("name", name.as_str())])];
+            if config.default_city.as_deref() == Some(format!("@{}", name).as_str()) {
+                config.default_city = None;
+                messages.push(t("location.default_cleared", &[("name", name.as_str())]));
+            }
+            messages
+        }
+        LocationCommand::List => {
+            if config.locations.is_empty() {
+                println!("{}", t("location.none", &[]));
+            }
+            let width = config.locations.keys().map(|name| name.len()).max();
+            for (name, location) in &config.locations {
+                println!(
+                    "@{:<width$}  {} ({}, {})",
+                    name,
+                    location.display_name(),
+                    format_number(location.lat),
+                    format_number(location.lon),
+                    width = width.unwrap_or_default()
+                );
+            }
+            return;
+        }
+        LocationCommand::Rename { name, new_name } => {
+            if config.locations.contains_key(&new_name) {
+                Cli::command()
+                    .error(
+                        ErrorKind::ValueValidation,
+                        t("location.exists", &[("name", new_name.as_str())]),
+                    )
+                    .exit();
+            }
+            let Some(location) = config.locations.remove(&name) else {
+                exit_with_error(WeatherError::UnknownLocation(format!("@{}", name)));
+            };
+            config.locations.insert(new_name.clone(), location);
+            // Keep the default pointing at the same place
+            if config.default_city.as_deref() == Some(format!("@{}", name).as_str()) {
+                config.default_city = Some(format!("@{}", new_name));
+            }
+            vec![t(
+                "location.renamed",
+                &[("name", name.as_str()), ("new_name", new_name.as_str())],
+            )]
+        }
+    };
+    if let Err(e) = config.save() {
+        exit_with_config_error(e);
+    }
+    for message in messages {
+        println!("{}", message);
+    }
+}
+
+/// Get the location to fetch the weather for
+///
+/// Falls back to the default location of the configuration and resolves
+/// saved locations. Exits if there is no location at all.
+///
+/// # Returns
+///
+/// The query and the saved location it was resolved from, if any
+fn location_query<'a>(
+    location: &LocationArgs,
+    config: &'a Config,
+) -> (LocationQuery, Option<&'a SavedLocation>) {
+    let query = location
+        .to_query()
+        .or_else(|| {
+            let city = config.default_city.as_deref()?.trim();
+            Some(LocationQuery::City(city.to_string())).filter(|_| !city.is_empty())
+        })
+        .unwrap_or_else(|| {
+            Cli::command()
+                .error(
+                    ErrorKind::MissingRequiredArgument,
+                    t("location.missing", &[]),
+                )
+                .exit()
+        });
+    let saved = saved_location(&query, config);
+    (saved.map_or(query, SavedLocation::query), saved)
+}
+
+/// Look up the saved location a query (`@name`) refers to
+///
+/// Exits with an unknown location error if there is no such location.
+///
+/// # Returns
+///
+/// The saved location or `None` if the query is not a saved location
+fn saved_location<'a>(query: &LocationQuery, config: &'a Config) -> Option<&'a SavedLocation> {
+    let name = query.saved_name()?;
+    match config.locations.get(name) {
+        Some(location) => Some(location),
+        None => exit_with_error(WeatherError::UnknownLocation(query.to_string())),
+    }
+}
+
+/// Show the name of a saved location instead of the resolved one
+///
+/// Providers name coordinates after the nearest place or not at all.
+fn keep_saved_name(location: &mut WeatherLocation, saved: Option<&SavedLocation>) {
+    if let Some(saved) = saved {
+        location.name = saved.name.clone();
+        location.region = saved.region.clone();
+        location.country = saved.country.clone();
+    }
+}
+
+/// Format a setting for display, hiding most of the API key
+fn display_value(key: &str, value: &str) -> String {
+    if key != "api_key" {
+        return value.to_string();
+    }
+    let visible: String = value
+        .chars()
+        .rev()
+        .take(4)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    format!("****{}", visible)
+}
+
+/// Open a file in the user's editor and wait until it is closed
+///
+/// The editor is taken from `$VISUAL`, then `$EDITOR` and defaults to `vi`
+/// (`notepad` on Windows). Arguments in the variables are passed on.
+fn edit_file(path: &Path) {
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let result = std::process::Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status();
+    let error = match result {
+        Ok(status) if status.success() => return,
+        Ok(status) => status.to_string(),
+        Err(e) => e.to_string(),
+    };
+    eprintln!(
+        "{}",
+        t(
+            "config.editor_error",
+            &[("editor", editor.as_str()), ("message", error.as_str())]
+        )
+    );
+    std::process::exit(1);
+}
+
+/// Select the language of the app
+///
+/// Uses the configured language, then the locale of the environment and
+/// falls back to English. Warns if the configured language is not available.
+fn select_language(configured: Option<&str>) -> String {
+    let configured = configured.filter(|lang| !lang.trim().is_empty());
+    if let Some(lang) = configured {
+        if let Some(lang) = match_language(lang) {
+            return lang;
+        }
+        Lingua::set_language(DEFAULT_LANGUAGE).ok();
+        eprintln!("{}", t("language.not_available", &[("lang", lang)]));
+    }
+    detect_language().unwrap_or_else(|| DEFAULT_LANGUAGE.to_string())
+}
+
+/// Save the language to the configuration file
+///
+/// Only the file is changed, settings from the environment are not written.
+fn save_language(lang: &str) {
+    let result = Config::load_file().and_then(|mut config| {
+        config.language = Some(lang.to_string());
+        config.save()
+    });
+    if let Err(e) = result {
+        exit_with_config_error(e);
+    }
+}
+
+/// Print a translated configuration error and exit
+fn exit_with_config_error(error: ConfigError) -> ! {
+    eprintln!(
+        "{}",
+        translate("error.config", &[("message", error.to_string().as_str())])
+            .unwrap_or_else(|| format!("Error: {}", error))
+    );
+    std::process::exit(1);
+}
+
+/// Print a translated error message and exit with the error's exit code
+fn exit_with_error(error: WeatherError) -> ! {
+    eprintln!(
+        "{}",
+        translate("error.error", &[("message", error.localized().as_str())])
+            .unwrap_or_else(|| format!("Error: {}", error))
+    );
+    std::process::exit(error.exit_code());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn invalid_range() -> String {
+        t(
+            "history.invalid_range",
+            &[("max_days", MAX_HISTORY_DAYS.to_string().as_str())],
+        )
+    }
+
+    #[test]
+    fn history_defaults_to_a_single_day() {
+        assert_eq!(history_end(day(10), None, day(18)), Ok(day(10)));
+        assert_eq!(history_end(day(18), None, day(18)), Ok(day(18)));
+    }
+
+    #[test]
+    fn history_range_is_limited() {
+        let start = NaiveDate::from_ymd_opt(2026, 9, 1).unwrap();
+        let last = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+
+        // 1 September to 1 October are 31 days
+        assert_eq!(history_end(start, Some(last), day(18)), Ok(last));
+        assert_eq!(
+            history_end(start, Some(day(2)), day(18)),
+            Err(invalid_range())
+        );
+    }
+
+    #[test]
+    fn history_end_must_not_be_before_the_start() {
+        assert_eq!(
+            history_end(day(10), Some(day(9)), day(18)),
+            Err(invalid_range())
+        );
+    }
+
+    #[test]
+    fn history_must_not_reach_into_the_future() {
+        let future = Err(t("history.future_date", &[]));
+
+        assert_eq!(history_end(day(19), None, day(18)), future);
+        assert_eq!(history_end(day(17), Some(day(19)), day(18)), future);
+    }
+}
@@ -0,0 +1,440 @@
+use csv::WriterBuilder;
+
+use crate::weather::{
+    Alert, Astronomy, ForecastDay, ForecastWeather, Hour, SearchLocation, Weather,
+};
+
+const CURRENT_HEADER: [&str; 25] = [
+    "location",
+    "last_updated",
+    "condition",
+    "temp_c",
+    "temp_f",
+    "feelslike_c",
+    "feelslike_f",
+    "humidity_pct",
+    "cloud_pct",
+    "wind_kph",
+    "wind_mph",
+    "wind_degree",
+    "wind_dir",
+    "gust_kph",
+    "gust_mph",
+    "pressure_mb",
+    "pressure_in",
+    "precip_mm",
+    "precip_in",
+    "vis_km",
+    "vis_miles",
+    "uv",
+    "is_day",
+    "lat",
+    "lon",
+];
+
+const DAY_HEADER: [&str; 21] = [
+    "date",
+    "condition",
+    "min_temp_c",
+    "min_temp_f",
+    "max_temp_c",
+    "max_temp_f",
+    "avg_temp_c",
+    "avg_temp_f",
+    "max_wind_kph",
+    "max_wind_mph",
+    "precip_mm",
+    "precip_in",
+    "snow_cm",
+    "avg_humidity_pct",
+    "avg_vis_km",
+    "avg_vis_miles",
+    "chance_of_rain_pct",
+    "chance_of_snow_pct",
+    "uv",
+    "sunrise",
+    "sunset",
+];
+
+const ALERT_HEADER: [&str; 8] = [
+    "headline",
+    "event",
+    "severity",
+    "urgency",
+    "areas",
+    "effective",
+    "expires",
+    "description",
+];
+
+const ASTRONOMY_HEADER: [&str; 13] = [
+    "date",
+    "sunrise",
+    "sunset",
+    "solar_noon",
+    "day_length_s",
+    "day_length_change_s",
+    "is_sun_up",
+    "moonrise",
+    "moonset",
+    "moon_phase",
+    "moon_illumination_pct",
+    "is_moon_up",
+    "location",
+];
+
+const LOCATION_HEADER: [&str; 7] = ["index", "name", "region", "country", "lat", "lon", "id"];
+
+const HOUR_HEADER: [&str; 26] = [
+    "time",
+    "condition",
+    "temp_c",
+    "temp_f",
+    "feelslike_c",
+    "feelslike_f",
+    "dewpoint_c",
+    "dewpoint_f",
+    "humidity_pct",
+    "cloud_pct",
+    "wind_kph",
+    "wind_mph",
+    "wind_degree",
+    "wind_dir",
+    "gust_kph",
+    "gust_mph",
+    "pressure_mb",
+    "pressure_in",
+    "precip_mm",
+    "precip_in",
+    "snow_cm",
+    "chance_of_rain_pct",
+    "chance_of_snow_pct",
+    "vis_km",
+    "vis_miles",
+    "uv",
+];
+
+impl Weather {
+    /// Serialize the current weather as a delimited table with a header row
+    ///
+    /// # Arguments
+    ///
+    /// * `delimiter` - The field delimiter, e.g. `b','` or `b'\t'`
+    ///
+    /// # Errors
+    ///
+    /// If a row cannot be written
+    pub fn to_csv(&self, delimiter: u8) -> Result<String, csv::Error> {
+        let current = &self.current;
+        let row = vec![
+            self.location.name.clone(),
+            current.last_updated.clone(),
+            current.condition.text.clone(),
+            current.temp_c.to_string(),
+            current.temp_f.to_string(),
+            current.feelslike_c.to_string(),
+            current.feelslike_f.to_string(),
+            current.humidity.to_string(),
+            current.cloud.to_string(),
+            current.wind_kph.to_string(),
+            current.wind_mph.to_string(),
+            current.wind_degree.to_string(),
+            current.wind_dir.clone(),
+            current.gust_kph.to_string(),
+            current.gust_mph.to_string(),
+            current.pressure_mb.to_string(),
+            current.pressure_in.to_string(),
+            current.precip_mm.to_string(),
+            current.precip_in.to_string(),
+            current.vis_km.to_string(),
+            current.vis_miles.to_string(),
+            current.uv.to_string(),
+            current.is_day.to_string(),
+            self.location.lat.to_string(),
+            self.location.lon.to_string(),
+        ];
+        write_table(&CURRENT_HEADER, vec![row], delimiter)
+    }
+}
+
+impl ForecastWeather {
+    /// Serialize the forecast as a delimited table with a header row
+    ///
+    /// # Arguments
+    ///
+    /// * `delimiter` - The field delimiter, e.g. `b','` or `b'\t'`
+    /// * `hourly` - Write one row per hour instead of one row per day
+    ///
+    /// # Errors
+    ///
+    /// If a row cannot be written
+    pub fn to_csv(&self, delimiter: u8, hourly: bool) -> Result<String, csv::Error> {
+        let days = &self.forecast.forecastday;
+        if hourly {
+            let rows = days
+                .iter()
+                .flat_map(|day| day.hour.iter().map(hour_row))
+                .collect();
+            write_table(&HOUR_HEADER, rows, delimiter)
+        } else {
+            write_table(&DAY_HEADER, days.iter().map(day_row).collect(), delimiter)
+        }
+    }
+
+    /// Serialize the weather alerts as a delimited table with a header row
+    ///
+    /// # Arguments
+    ///
+    /// * `delimiter` - The field delimiter, e.g. `b','` or `b'\t'`
+    ///
+    /// # Errors
+    ///
+    /// If a row cannot be written
+    pub fn alerts_to_csv(&self, delimiter: u8) -> Result<String, csv::Error> {
+        let rows = self.alerts.iter().map(alert_row).collect();
+        write_table(&ALERT_HEADER, rows, delimiter)
+    }
+}
+
+impl Astronomy {
+    /// Serialize the sun and moon data as a delimited table with a header row
+    ///
+    /// # Arguments
+    ///
+    /// * `delimiter` - The field delimiter, e.g. `b','` or `b'\t'`
+    ///
+    /// # Errors
+    ///
+    /// If the row cannot be written
+    pub fn to_csv(&self, delimiter: u8) -> Result<String, csv::Error> {
+        let astro = &self.astro;
+        let optional = |value: Option<i64>| value.map(|v| v.to_string()).unwrap_or_default();
+        let row = vec![
+            self.date.clone(),
+            astro.sunrise.clone(),
+            astro.sunset.clone(),
+            astro
+                .solar_noon()
+                .map(|time| time.format("%H:%M").to_string())
+                .unwrap_or_default(),
+            optional(astro.day_length().map(|length| length.num_seconds())),
+            optional(self.day_length_change().map(|change| change.num_seconds())),
+            astro.is_sun_up.to_string(),
+            astro.moonrise.clone(),
+            astro.moonset.clone(),
+            astro.moon_phase.clone(),
+            astro.moon_illumination.to_string(),
+            astro.is_moon_up.to_string(),
+            self.location.name.clone(),
+        ];
+        write_table(&ASTRONOMY_HEADER, vec![row], delimiter)
+    }
+}
+
+/// Serialize search results as a delimited table with a header row
+///
+/// # Arguments
+///
+/// * `locations` - The locations to serialize
+/// * `delimiter` - The field delimiter, e.g. `b','` or `b'\t'`
+///
+/// # Errors
+///
+/// If a row cannot be written
+pub fn locations_to_csv(locations: &[SearchLocation], delimiter: u8) -> Result<String, csv::Error> {
+    let rows = locations
+        .iter()
+        .enumerate()
+        .map(|(i, location)| {
+            vec![
+                (i + 1).to_string(),
+                location.name.clone(),
+                location.region.clone(),
+                location.country.clone(),
+                location.lat.to_string(),
+                location.lon.to_string(),
+                location.id.to_string(),
+            ]
+        })
+        .collect();
+    write_table(&LOCATION_HEADER, rows, delimiter)
+}
+
+/// Write a header and the rows into a string
+fn write_table(
+    header: &[&str],
+    rows: Vec<Vec<String>>,
+    delimiter: u8,
+) -> Result<String, csv::Error> {
+    let mut writer = WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(Vec::new());
+    writer.write_record(header)?;
+    for row in rows {
+        writer.write_record(&row)?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| e.into_error())?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn day_row(forecast_day: &ForecastDay) -> Vec<String> {
+    let day = &forecast_day.day;
+    vec![
+        forecast_day.date.clone(),
+        day.condition.text.clone(),
+        day.mintemp_c.to_string(),
+        day.mintemp_f.to_string(),
+        day.maxtemp_c.to_string(),
+        day.maxtemp_f.to_string(),
+        day.avgtemp_c.to_string(),
+        day.avgtemp_f.to_string(),
+        day.maxwind_kph.to_string(),
+        day.maxwind_mph.to_string(),
+        day.totalprecip_mm.to_string(),
+        day.totalprecip_in.to_string(),
+        day.totalsnow_cm.to_string(),
+        day.avghumidity.to_string(),
+        day.avgvis_km.to_string(),
+        day.avgvis_miles.to_string(),
+        day.daily_chance_of_rain.to_string(),
+        day.daily_chance_of_snow.to_string(),
+        day.uv.to_string(),
+        forecast_day.astro.sunrise.clone(),
+        forecast_day.astro.sunset.clone(),
+    ]
+}
+
+fn hour_row(hour: &Hour) -> Vec<String> {
+    vec![
+        hour.time.clone(),
+        hour.condition.text.clone(),
+        hour.temp_c.to_string(),
+        hour.temp_f.to_string(),
+        hour.feelslike_c.to_string(),
+        hour.feelslike_f.to_string(),
+        hour.dewpoint_c.to_string(),
+        hour.dewpoint_f.to_string(),
+        hour.humidity.to_string(),
+        hour.cloud.to_string(),
+        hour.wind_kph.to_string(),
+        hour.wind_mph.to_string(),
+        hour.wind_degree.to_string(),
+        hour.wind_dir.clone(),
+        hour.gust_kph.to_string(),
+        hour.gust_mph.to_string(),
+        hour.pressure_mb.to_string(),
+        hour.pressure_in.to_string(),
+        hour.precip_mm.to_string(),
+        hour.precip_in.to_string(),
+        hour.snow_cm.to_string(),
+        hour.chance_of_rain.to_string(),
+        hour.chance_of_snow.to_string(),
+        hour.vis_km.to_string(),
+        hour.vis_miles.to_string(),
+        hour.uv.to_string(),
+    ]
+}
+
+fn alert_row(alert: &Alert) -> Vec<String> {
+    vec![
+        alert.headline.clone(),
+        alert.event.clone(),
+        alert.severity.clone(),
+        alert.urgency.clone(),
+        alert.areas.clone(),
+        alert.effective.clone(),
+        alert.expires.clone(),
+        alert.description.clone(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CURRENT: &str = include_str!("../../tests/fixtures/weather/current.json");
+    const FORECAST: &str = include_str!("../../tests/fixtures/weather/forecast.json");
+
+    fn lines(csv: Result<String, csv::Error>) -> Vec<String> {
+        csv.unwrap().lines().map(str::to_string).collect()
+    }
+
+    fn location(name: &str, region: &str) -> SearchLocation {
+        SearchLocation {
+            id: 1,
+            name: name.to_string(),
+            region: region.to_string(),
+            country: "USA".to_string(),
+            lat: 38.9,
+            lon: -77.04,
+        }
+    }
+
+    #[test]
+    fn tables_start_with_their_header() {
+        let weather: Weather = serde_json::from_str(CURRENT).unwrap();
+        let lines = lines(weather.to_csv(b','));
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], CURRENT_HEADER.join(","));
+        assert!(lines[1].starts_with("Berlin,2026-10-18 14:15,Overcast,17,62.6,"));
+        assert!(lines[1].ends_with(",52.52437,13.41053"));
+    }
+
+    #[test]
+    fn forecasts_have_a_row_per_day_or_hour() {
+        let forecast: ForecastWeather = serde_json::from_str(FORECAST).unwrap();
+
+        let days = lines(forecast.to_csv(b',', false));
+        assert_eq!(days[0], DAY_HEADER.join(","));
+        assert_eq!(days.len(), 2);
+        assert!(days[1].starts_with("2026-10-18,"));
+
+        let hours = lines(forecast.to_csv(b',', true));
+        assert_eq!(hours[0], HOUR_HEADER.join(","));
+        assert_eq!(hours.len(), 4);
+        assert!(hours[2].starts_with("2026-10-18 13:00,"));
+
+        let alerts = lines(forecast.alerts_to_csv(b','));
+        assert_eq!(alerts[0], ALERT_HEADER.join(","));
+        assert_eq!(alerts.len(), 2);
+    }
+
+    #[test]
+    fn custom_delimiters_are_used() {
+        let locations = [location("Washington", "District of Columbia")];
+
+        assert_eq!(
+            lines(locations_to_csv(&locations, b';')),
+            [
+                "index;name;region;country;lat;lon;id",
+                "1;Washington;District of Columbia;USA;38.9;-77.04;1",
+            ]
+        );
+        assert_eq!(
+            lines(locations_to_csv(&locations, b'\t'))[1],
+            "1\tWashington\tDistrict of Columbia\tUSA\t38.9\t-77.04\t1"
+        );
+    }
+
+    #[test]
+    fn fields_containing_the_delimiter_are_quoted() {
+        let locations = [location("Washington, D.C.", "Columbia; DC")];
+
+        assert_eq!(
+            lines(locations_to_csv(&locations, b','))[1],
+            "1,\"Washington, D.C.\",Columbia; DC,USA,38.9,-77.04,1"
+        );
+        assert_eq!(
+            lines(locations_to_csv(&locations, b';'))[1],
+            "1;Washington, D.C.;\"Columbia; DC\";USA;38.9;-77.04;1"
+        );
+
+        let quoted = [location("The \"Capital\"", "")];
+        assert_eq!(
+            lines(locations_to_csv(&quoted, b','))[1],
+            "1,\"The \"\"Capital\"\"\",,USA,38.9,-77.04,1"
+        );
+    }
+}
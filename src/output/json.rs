@@ -0,0 +1,718 @@
+use serde::Serialize;
+
+use crate::weather::{
+    AirQuality, Alert, Astro, Astronomy, ForecastDay, ForecastWeather, Hour, SearchLocation,
+    Weather, WeatherCondition, WeatherLocation,
+};
+
+/// Version of the JSON schema, increased on every incompatible change
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct JsonCurrentReport<'a> {
+    schema_version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stale_since: Option<&'a str>,
+    location: JsonLocation<'a>,
+    current: JsonCurrent<'a>,
+}
+
+#[derive(Serialize)]
+struct JsonForecastReport<'a> {
+    schema_version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stale_since: Option<&'a str>,
+    location: JsonLocation<'a>,
+    #[serde(skip_serializing_if = "<[Alert]>::is_empty")]
+    alerts: &'a [Alert],
+    days: Vec<JsonDay<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonAlertsReport<'a> {
+    schema_version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stale_since: Option<&'a str>,
+    location: JsonLocation<'a>,
+    alerts: &'a [Alert],
+}
+
+#[derive(Serialize)]
+struct JsonLocation<'a> {
+    name: &'a str,
+    region: &'a str,
+    country: &'a str,
+    lat: f64,
+    lon: f64,
+    timezone: &'a str,
+    localtime: &'a str,
+}
+
+#[derive(Serialize)]
+struct JsonCondition<'a> {
+    text: &'a str,
+    code: i64,
+}
+
+#[derive(Serialize)]
+struct Temperature {
+    c: f64,
+    f: f64,
+}
+
+#[derive(Serialize)]
+struct Speed {
+    kph: f64,
+    mph: f64,
+}
+
+#[derive(Serialize)]
+struct Wind<'a> {
+    kph: f64,
+    mph: f64,
+    degree: i64,
+    direction: &'a str,
+}
+
+#[derive(Serialize)]
+struct Pressure {
+    mb: f64,
+    #[serde(rename = "in")]
+    inches: f64,
+}
+
+#[derive(Serialize)]
+struct Precipitation {
+    mm: f64,
+    #[serde(rename = "in")]
+    inches: f64,
+}
+
+#[derive(Serialize)]
+struct Distance {
+    km: f64,
+    miles: f64,
+}
+
+#[derive(Serialize)]
+struct JsonCurrent<'a> {
+    last_updated: &'a str,
+    condition: JsonCondition<'a>,
+    is_day: bool,
+    temperature: Temperature,
+    feels_like: Temperature,
+    humidity: i64,
+    cloud: i64,
+    wind: Wind<'a>,
+    gust: Speed,
+    pressure: Pressure,
+    precipitation: Precipitation,
+    visibility: Distance,
+    uv: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    air_quality: Option<&'a AirQuality>,
+}
+
+#[derive(Serialize)]
+struct JsonDay<'a> {
+    date: &'a str,
+    condition: JsonCondition<'a>,
+    min_temperature: Temperature,
+    max_temperature: Temperature,
+    avg_temperature: Temperature,
+    max_wind: Speed,
+    precipitation: Precipitation,
+    snow_cm: f64,
+    avg_humidity: i64,
+    avg_visibility: Distance,
+    chance_of_rain: i64,
+    chance_of_snow: i64,
+    uv: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    air_quality: Option<&'a AirQuality>,
+    astro: JsonAstro<'a>,
+    hours: Vec<JsonHour<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonAstro<'a> {
+    sunrise: &'a str,
+    sunset: &'a str,
+    moonrise: &'a str,
+    moonset: &'a str,
+    moon_phase: &'a str,
+    moon_illumination: f64,
+}
+
+#[derive(Serialize)]
+struct JsonAstronomyReport<'a> {
+    schema_version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stale_since: Option<&'a str>,
+    location: JsonLocation<'a>,
+    date: &'a str,
+    #[serde(flatten)]
+    astro: JsonAstro<'a>,
+    is_sun_up: bool,
+    is_moon_up: bool,
+    solar_noon: Option<String>,
+    day_length_seconds: Option<i64>,
+    day_length_change_seconds: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct JsonSearchReport<'a> {
+    schema_version: u32,
+    query: &'a str,
+    locations: &'a [SearchLocation],
+}
+
+#[derive(Serialize)]
+struct JsonHour<'a> {
+    time: &'a str,
+    condition: JsonCondition<'a>,
+    is_day: bool,
+    temperature: Temperature,
+    feels_like: Temperature,
+    dew_point: Temperature,
+    humidity: i64,
+    cloud: i64,
+    wind: Wind<'a>,
+    gust: Speed,
+    pressure: Pressure,
+    precipitation: Precipitation,
+    snow_cm: f64,
+    chance_of_rain: i64,
+    chance_of_snow: i64,
+    visibility: Distance,
+    uv: f64,
+}
+
+impl Weather {
+    /// Serialize the current weather into the documented JSON schema
+    ///
+    /// # Arguments
+    ///
+    /// * `pretty` - Whether to indent the output
+    ///
+    /// # Errors
+    ///
+    /// If the data cannot be serialized
+    pub fn to_json(&self, pretty: bool) -> serde_json::Result<String> {
+        let current = &self.current;
+        let report = JsonCurrentReport {
+            schema_version: SCHEMA_VERSION,
+            stale_since: self.stale_since.as_deref(),
+            location: json_location(&self.location),
+            current: JsonCurrent {
+                last_updated: &current.last_updated,
+                condition: json_condition(&current.condition),
+                is_day: current.is_day == 1,
+                temperature: Temperature {
+                    c: current.temp_c,
+                    f: current.temp_f,
+                },
+                feels_like: Temperature {
+                    c: current.feelslike_c,
+                    f: current.feelslike_f,
+                },
+                humidity: current.humidity,
+                cloud: current.cloud,
+                wind: Wind {
+                    kph: current.wind_kph,
+                    mph: current.wind_mph,
+                    degree: current.wind_degree,
+                    direction: &current.wind_dir,
+                },
+                gust: Speed {
+                    kph: current.gust_kph,
+                    mph: current.gust_mph,
+                },
+                pressure: Pressure {
+                    mb: current.pressure_mb,
+                    inches: current.pressure_in,
+                },
+                precipitation: Precipitation {
+                    mm: current.precip_mm,
+                    inches: current.precip_in,
+                },
+                visibility: Distance {
+                    km: current.vis_km,
+                    miles: current.vis_miles,
+                },
+                uv: current.uv,
+                air_quality: current.air_quality.as_ref(),
+            },
+        };
+        to_string(&report, pretty)
+    }
+}
+
+impl ForecastWeather {
+    /// Serialize the forecast into the documented JSON schema
+    ///
+    /// # Arguments
+    ///
+    /// * `pretty` - Whether to indent the output
+    ///
+    /// # Errors
+    ///
+    /// If the data cannot be serialized
+    pub fn to_json(&self, pretty: bool) -> serde_json::Result<String> {
+        let report = JsonForecastReport {
+            schema_version: SCHEMA_VERSION,
+            stale_since: self.stale_since.as_deref(),
+            location: json_location(&self.location),
+            alerts: &self.alerts,
+            days: self.forecast.forecastday.iter().map(json_day).collect(),
+        };
+        to_string(&report, pretty)
+    }
+
+    /// Serialize the weather alerts into the documented JSON schema
+    ///
+    /// # Arguments
+    ///
+    /// * `pretty` - Whether to indent the output
+    ///
+    /// # Errors
+    ///
+    /// If the data cannot be serialized
+    pub fn alerts_to_json(&self, pretty: bool) -> serde_json::Result<String> {
+        let report = JsonAlertsReport {
+            schema_version: SCHEMA_VERSION,
+            stale_since: self.stale_since.as_deref(),
+            location: json_location(&self.location),
+            alerts: &self.alerts,
+        };
+        to_string(&report, pretty)
+    }
+}
+
+impl Astronomy {
+    /// Serialize the sun and moon data into the documented JSON schema
+    ///
+    /// # Arguments
+    ///
+    /// * `pretty` - Whether to indent the output
+    ///
+    /// # Errors
+    ///
+    /// If the data cannot be serialized
+    pub fn to_json(&self, pretty: bool) -> serde_json::Result<String> {
+        let astro = &self.astro;
+        let report = JsonAstronomyReport {
+            schema_version: SCHEMA_VERSION,
+            stale_since: self.stale_since.as_deref(),
+            location: json_location(&self.location),
+            date: &self.date,
+            astro: json_astro(astro),
+            is_sun_up: astro.is_sun_up == 1,
+            is_moon_up: astro.is_moon_up == 1,
+            solar_noon: astro
+                .solar_noon()
+                .map(|time| time.format("%H:%M").to_string()),
+            day_length_seconds: astro.day_length().map(|length| length.num_seconds()),
+            day_length_change_seconds: self.day_length_change().map(|change| change.num_seconds()),
+        };
+        to_string(&report, pretty)
+    }
+}
+
+/// Serialize search results into the documented JSON schema
+///
+/// # Arguments
+///
+/// * `query` - The search query the locations were found for
+/// * `locations` - The locations to serialize
+/// * `pretty` - Whether to indent the output
+///
+/// # Errors
+///
+/// If the data cannot be serialized
+pub fn locations_to_json(
+    query: &str,
+    locations: &[SearchLocation],
+    pretty: bool,
+) -> serde_json::Result<String> {
+    let report = JsonSearchReport {
+        schema_version: SCHEMA_VERSION,
+        query,
+        locations,
+    };
+    to_string(&report, pretty)
+}
+
+fn to_string<T: Serialize>(value: &T, pretty: bool) -> serde_json::Result<String> {
+    if pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    }
+}
+
+fn json_location(location: &WeatherLocation) -> JsonLocation<'_> {
+    JsonLocation {
+        name: &location.name,
+        region: &location.region,
+        country: &location.country,
+        lat: location.lat,
+        lon: location.lon,
+        timezone: &location.tz_id,
+        localtime: &location.localtime,
+    }
+}
+
+fn json_condition(condition: &WeatherCondition) -> JsonCondition<'_> {
+    JsonCondition {
+        text: &condition.text,
+        code: condition.code,
+    }
+}
+
+fn json_day(forecast_day: &ForecastDay) -> JsonDay<'_> {
+    let day = &forecast_day.day;
+    let astro = &forecast_day.astro;
+    JsonDay {
+        date: &forecast_day.date,
+        condition: json_condition(&day.condition),
+        min_temperature: Temperature {
+            c: day.mintemp_c,
+            f: day.mintemp_f,
+        },
+        max_temperature: Temperature {
+            c: day.maxtemp_c,
+            f: day.maxtemp_f,
+        },
+        avg_temperature: Temperature {
+            c: day.avgtemp_c,
+            f: day.avgtemp_f,
+        },
+        max_wind: Speed {
+            kph: day.maxwind_kph,
+            mph: day.maxwind_mph,
+        },
+        precipitation: Precipitation {
+            mm: day.totalprecip_mm,
+            inches: day.totalprecip_in,
+        },
+        snow_cm: day.totalsnow_cm,
+        avg_humidity: day.avghumidity,
+        avg_visibility: Distance {
+            km: day.avgvis_km,
+            miles: day.avgvis_miles,
+        },
+        chance_of_rain: day.daily_chance_of_rain,
+        chance_of_snow: day.daily_chance_of_snow,
+        uv: day.uv,
+        air_quality: day.air_quality.as_ref(),
+        astro: json_astro(astro),
+        hours: forecast_day.hour.iter().map(json_hour).collect(),
+    }
+}
+
+fn json_hour(hour: &Hour) -> JsonHour<'_> {
+    JsonHour {
+        time: &hour.time,
+        condition: json_condition(&hour.condition),
+        is_day: hour.is_day == 1,
+        temperature: Temperature {
+            c: hour.temp_c,
+            f: hour.temp_f,
+        },
+        feels_like: Temperature {
+            c: hour.feelslike_c,
+            f: hour.feelslike_f,
+        },
+        dew_point: Temperature {
+            c: hour.dewpoint_c,
+            f: hour.dewpoint_f,
+        },
+        humidity: hour.humidity,
+        cloud: hour.cloud,
+        wind: Wind {
+            kph: hour.wind_kph,
+            mph: hour.wind_mph,
+            degree: hour.wind_degree,
+            direction: &hour.wind_dir,
+        },
+        gust: Speed {
+            kph: hour.gust_kph,
+            mph: hour.gust_mph,
+        },
+        pressure: Pressure {
+            mb: hour.pressure_mb,
+            inches: hour.pressure_in,
+        },
+        precipitation: Precipitation {
+            mm: hour.precip_mm,
+            inches: hour.precip_in,
+        },
+        snow_cm: hour.snow_cm,
+        chance_of_rain: hour.chance_of_rain,
+        chance_of_snow: hour.chance_of_snow,
+        visibility: Distance {
+            km: hour.vis_km,
+            miles: hour.vis_miles,
+        },
+        uv: hour.uv,
+    }
+}
+
+fn json_astro(astro: &Astro) -> JsonAstro<'_> {
+    JsonAstro {
+        sunrise: &astro.sunrise,
+        sunset: &astro.sunset,
+        moonrise: &astro.moonrise,
+        moonset: &astro.moonset,
+        moon_phase: &astro.moon_phase,
+        moon_illumination: astro.moon_illumination,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    const CURRENT: &str = include_str!("../../tests/fixtures/weather/current.json");
+    const FORECAST: &str = include_str!("../../tests/fixtures/weather/forecast.json");
+    const ASTRONOMY: &str = include_str!("../../tests/fixtures/weather/astronomy.json");
+
+    fn parse(json: serde_json::Result<String>) -> Value {
+        serde_json::from_str(&json.unwrap()).unwrap()
+    }
+
+    /// Get the sorted keys of a JSON object
+    fn keys(value: &Value) -> Vec<&str> {
+        let mut keys: Vec<&str> = value
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    const LOCATION_KEYS: [&str; 7] = [
+        "country",
+        "lat",
+        "localtime",
+        "lon",
+        "name",
+        "region",
+        "timezone",
+    ];
+    const AIR_QUALITY_KEYS: [&str; 8] = [
+        "co",
+        "gb_defra_index",
+        "no2",
+        "o3",
+        "pm10",
+        "pm2_5",
+        "so2",
+        "us_epa_index",
+    ];
+
+    #[test]
+    fn schema_version_is_pinned() {
+        // Changing the schema incompatibly requires a new version
+        assert_eq!(SCHEMA_VERSION, 1);
+    }
+
+    #[test]
+    fn current_weather_schema() {
+        let weather: Weather = serde_json::from_str(CURRENT).unwrap();
+        let json = parse(weather.to_json(false));
+
+        assert_eq!(keys(&json), ["current", "location", "schema_version"]);
+        assert_eq!(json["schema_version"], SCHEMA_VERSION);
+        assert_eq!(keys(&json["location"]), LOCATION_KEYS);
+        let current = &json["current"];
+        assert_eq!(
+            keys(current),
+            [
+                "air_quality",
+                "cloud",
+                "condition",
+                "feels_like",
+                "gust",
+                "humidity",
+                "is_day",
+                "last_updated",
+                "precipitation",
+                "pressure",
+                "temperature",
+                "uv",
+                "visibility",
+                "wind",
+            ]
+        );
+        assert_eq!(keys(&current["condition"]), ["code", "text"]);
+        assert_eq!(keys(&current["temperature"]), ["c", "f"]);
+        assert_eq!(keys(&current["gust"]), ["kph", "mph"]);
+        assert_eq!(
+            keys(&current["wind"]),
+            ["degree", "direction", "kph", "mph"]
+        );
+        assert_eq!(keys(&current["pressure"]), ["in", "mb"]);
+        assert_eq!(keys(&current["precipitation"]), ["in", "mm"]);
+        assert_eq!(keys(&current["visibility"]), ["km", "miles"]);
+        assert_eq!(keys(&current["air_quality"]), AIR_QUALITY_KEYS);
+        assert_eq!(current["is_day"], true);
+        assert_eq!(current["wind"]["direction"], "SSW");
+    }
+
+    #[test]
+    fn stale_data_is_marked() {
+        let mut weather: Weather = serde_json::from_str(CURRENT).unwrap();
+        weather.stale_since = Some("2026-10-18 12:00".to_string());
+        let json = parse(weather.to_json(true));
+
+        assert_eq!(json["stale_since"], "2026-10-18 12:00");
+    }
+
+    #[test]
+    fn forecast_schema() {
+        let forecast: ForecastWeather = serde_json::from_str(FORECAST).unwrap();
+        let json = parse(forecast.to_json(false));
+
+        assert_eq!(
+            keys(&json),
+            ["alerts", "days", "location", "schema_version"]
+        );
+        assert_eq!(
+            keys(&json["alerts"][0]),
+            [
+                "areas",
+                "description",
+                "effective",
+                "event",
+                "expires",
+                "headline",
+                "severity",
+                "urgency",
+            ]
+        );
+        let day = &json["days"][0];
+        assert_eq!(
+            keys(day),
+            [
+                "air_quality",
+                "astro",
+                "avg_humidity",
+                "avg_temperature",
+                "avg_visibility",
+                "chance_of_rain",
+                "chance_of_snow",
+                "condition",
+                "date",
+                "hours",
+                "max_temperature",
+                "max_wind",
+                "min_temperature",
+                "precipitation",
+                "snow_cm",
+                "uv",
+            ]
+        );
+        assert_eq!(keys(&day["air_quality"]), AIR_QUALITY_KEYS);
+        assert_eq!(
+            keys(&day["astro"]),
+            [
+                "moon_illumination",
+                "moon_phase",
+                "moonrise",
+                "moonset",
+                "sunrise",
+                "sunset",
+            ]
+        );
+        assert_eq!(
+            keys(&day["hours"][0]),
+            [
+                "chance_of_rain",
+                "chance_of_snow",
+                "cloud",
+                "condition",
+                "dew_point",
+                "feels_like",
+                "gust",
+                "humidity",
+                "is_day",
+                "precipitation",
+                "pressure",
+                "snow_cm",
+                "temperature",
+                "time",
+                "uv",
+                "visibility",
+                "wind",
+            ]
+        );
+        assert_eq!(day["hours"].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn forecast_without_alerts_has_no_alerts_key() {
+        let mut forecast: ForecastWeather = serde_json::from_str(FORECAST).unwrap();
+        forecast.alerts.clear();
+        let json = parse(forecast.to_json(false));
+
+        assert_eq!(keys(&json), ["days", "location", "schema_version"]);
+
+        let json = parse(forecast.alerts_to_json(false));
+        assert_eq!(keys(&json), ["alerts", "location", "schema_version"]);
+        assert_eq!(json["alerts"], Value::Array(Vec::new()));
+    }
+
+    #[test]
+    fn astronomy_schema() {
+        let astronomy: Astronomy = serde_json::from_str(ASTRONOMY).unwrap();
+        let json = parse(astronomy.to_json(false));
+
+        assert_eq!(
+            keys(&json),
+            [
+                "date",
+                "day_length_change_seconds",
+                "day_length_seconds",
+                "is_moon_up",
+                "is_sun_up",
+                "location",
+                "moon_illumination",
+                "moon_phase",
+                "moonrise",
+                "moonset",
+                "schema_version",
+                "solar_noon",
+                "sunrise",
+                "sunset",
+            ]
+        );
+        assert_eq!(json["solar_noon"], "12:51");
+        assert_eq!(json["day_length_seconds"], 38400);
+        assert_eq!(json["day_length_change_seconds"], Value::Null);
+    }
+
+    #[test]
+    fn search_schema() {
+        let locations = [SearchLocation {
+            id: 2950159,
+            name: "Berlin".to_string(),
+            region: "Land Berlin".to_string(),
+            country: "Germany".to_string(),
+            lat: 52.52437,
+            lon: 13.41053,
+        }];
+        let json = parse(locations_to_json("Berlin", &locations, false));
+
+        assert_eq!(keys(&json), ["locations", "query", "schema_version"]);
+        assert_eq!(
+            keys(&json["locations"][0]),
+            ["country", "id", "lat", "lon", "name", "region"]
+        );
+    }
+}
@@ -0,0 +1,50 @@
+mod csv;
+mod json;
+
+pub use csv::locations_to_csv;
+pub use json::{SCHEMA_VERSION, locations_to_json};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// The formats the weather data can be printed in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Human readable tables
+    #[default]
+    Table,
+    /// Machine readable JSON, see the README for the schema
+    Json,
+    /// Comma separated values with a header row
+    Csv,
+    /// Tab separated values with a header row
+    Tsv,
+}
+
+impl OutputFormat {
+    /// Get the field delimiter for the delimited formats
+    ///
+    /// # Arguments
+    ///
+    /// * `custom` - A delimiter that overrides the format's default
+    pub fn delimiter(&self, custom: Option<char>) -> u8 {
+        match (custom, self) {
+            (Some(delimiter), _) => delimiter as u8,
+            (None, OutputFormat::Tsv) => b'\t',
+            (None, _) => b',',
+        }
+    }
+}
+
+/// Parse a delimiter argument, which has to be a single ASCII character
+///
+/// `\t` is accepted for a tab.
+pub fn parse_delimiter(value: &str) -> Result<char, String> {
+    let mut chars = value.chars();
+    match (value, chars.next(), chars.next()) {
+        ("\\t", _, _) => Ok('\t'),
+        (_, Some(c), None) if c.is_ascii() => Ok(c),
+        _ => Err("the delimiter must be a single ASCII character".to_string()),
+    }
+}
@@ -0,0 +1,754 @@
+mod open_meteo;
+mod weatherapi;
+
+pub use open_meteo::OpenMeteo;
+pub use weatherapi::WeatherApi;
+
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{NaiveDate, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::cache::{Cache, CacheMode, Cacheable, CachedValue};
+use crate::client::HttpClient;
+use crate::error::WeatherError;
+use crate::location::LocationQuery;
+use crate::weather::{Astronomy, ForecastWeather, SearchLocation, Weather};
+
+/// The most days a forecast can be requested for
+pub const MAX_FORECAST_DAYS: u8 = 14;
+
+/// Result type returned by all provider calls
+pub type ProviderResult<T> = Result<T, WeatherError>;
+
+/// Optional data to request in addition to the weather
+#[derive(Debug, Clone, Default)]
+pub struct FetchOptions {
+    /// Include air quality data
+    pub air_quality: bool,
+    /// Include official weather alerts, only used for forecasts
+    pub alerts: bool,
+}
+
+/// A source of weather data
+///
+/// Implementations translate the requests into the provider's own API and
+/// map the responses onto the provider-neutral models from the `weather`
+/// module, so the printers never see the provider's JSON shape.
+pub trait WeatherProvider {
+    /// Fetch the current weather for a location
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The location to get the weather for
+    /// * `lang` - The language code for the condition texts
+    /// * `options` - Which optional data to include
+    fn current(
+        &self,
+        query: &LocationQuery,
+        lang: &str,
+        options: &FetchOptions,
+    ) -> impl Future<Output = ProviderResult<Weather>> + Send;
+
+    /// Fetch the forecast for a location
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The location to get the forecast for
+    /// * `days` - The number of days to get the forecast for
+    /// * `lang` - The language code for the condition texts
+    /// * `options` - Which optional data to include
+    fn forecast(
+        &self,
+        query: &LocationQuery,
+        days: usize,
+        lang: &str,
+        options: &FetchOptions,
+    ) -> impl Future<Output = ProviderResult<ForecastWeather>> + Send;
+
+    /// Fetch the observed weather of a single past day
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The location to get the weather for
+    /// * `date` - The day to get the weather for
+    /// * `lang` - The language code for the condition texts
+    fn history(
+        &self,
+        query: &LocationQuery,
+        date: NaiveDate,
+        lang: &str,
+    ) -> impl Future<Output = ProviderResult<ForecastWeather>> + Send;
+
+    /// Fetch the sun and moon data of a single day
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The location to get the data for
+    /// * `date` - The day to get the data for
+    /// * `lang` - The language code for the location names, if supported
+    fn astronomy(
+        &self,
+        query: &LocationQuery,
+        date: NaiveDate,
+        lang: &str,
+    ) -> impl Future<Output = ProviderResult<Astronomy>> + Send;
+
+    /// Search for locations matching a query
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The (partial) location name to search for
+    /// * `lang` - The language code for the location names, if supported
+    fn search(
+        &self,
+        query: &str,
+        lang: &str,
+    ) -> impl Future<Output = ProviderResult<Vec<SearchLocation>>> + Send;
+}
+
+/// The weather providers that can be selected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+pub enum ProviderKind {
+    /// weatherapi.com, requires the `WEATHER_API` key
+    #[clap(name = "weatherapi")]
+    #[serde(rename = "weatherapi")]
+    WeatherApi,
+    /// Open-Meteo, works without an API key
+    #[clap(name = "open-meteo")]
+    #[serde(rename = "open-meteo")]
+    OpenMeteo,
+}
+
+impl fmt::Display for ProviderKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProviderKind::WeatherApi => write!(f, "weatherapi"),
+            ProviderKind::OpenMeteo => write!(f, "open-meteo"),
+        }
+    }
+}
+
+impl FromStr for ProviderKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        <ProviderKind as ValueEnum>::from_str(s, true)
+    }
+}
+
+/// How long cached responses of each endpoint are considered fresh
+const CURRENT_TTL: Duration = Duration::from_secs(10 * 60);
+const FORECAST_TTL: Duration = Duration::from_secs(60 * 60);
+const HISTORY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+const ASTRONOMY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+const SEARCH_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// A provider selected at runtime
+///
+/// Dispatches every call to the wrapped provider implementation, through the
+/// response cache if one is configured.
+#[derive(Debug)]
+pub struct Provider {
+    kind: ProviderKind,
+    backend: Backend,
+    /// The base URL override of the client, part of every cache key
+    base_url: Option<String>,
+    cache: Option<Cache>,
+    /// Never fetch, even if there is no cache
+    offline: bool,
+}
+
+#[derive(Debug)]
+enum Backend {
+    WeatherApi(WeatherApi),
+    OpenMeteo(OpenMeteo),
+}
+
+impl Provider {
+    /// Create the provider for the given kind
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The provider to create
+    /// * `client` - The HTTP client the provider sends its requests with
+    pub fn new(kind: ProviderKind, client: HttpClient) -> Self {
+        let base_url = client.base_url().map(str::to_string);
+        let backend = match kind {
+            ProviderKind::WeatherApi => Backend::WeatherApi(WeatherApi::new(client)),
+            ProviderKind::OpenMeteo => Backend::OpenMeteo(OpenMeteo::new(client)),
+        };
+        Provider {
+            kind,
+            backend,
+            base_url,
+            cache: None,
+            offline: false,
+        }
+    }
+
+    /// Cache the responses of the provider
+    ///
+    /// # Arguments
+    ///
+    /// * `cache` - The cache to use, `None` disables caching
+    pub fn with_cache(mut self, cache: Option<Cache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Only answer requests from the cache, never send one
+    ///
+    /// Requests fail with [`WeatherError::NotCached`] if the cache has no
+    /// entry for them or there is no cache at all.
+    ///
+    /// # Arguments
+    ///
+    /// * `offline` - Whether the network must not be used
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Use the given API key for providers that require one
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - The API key, `None` keeps the provider's default lookup
+    pub fn with_api_key(mut self, api_key: Option<String>) -> Self {
+        if let Backend::WeatherApi(api) = self.backend {
+            self.backend = Backend::WeatherApi(api.with_api_key(api_key));
+        }
+        self
+    }
+
+    /// Fetch the observed weather for a range of past days
+    ///
+    /// Every day is requested on its own and the days are merged into one
+    /// result, so the range is not limited by the provider's history
+    /// endpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The location to get the weather for
+    /// * `start` - The first day, inclusive
+    /// * `end` - The last day, inclusive
+    /// * `lang` - The language code for the condition texts
+    ///
+    /// # Errors
+    ///
+    /// If any of the days cannot be fetched
+    pub async fn history_range(
+        &self,
+        query: &LocationQuery,
+        start: NaiveDate,
+        end: NaiveDate,
+        lang: &str,
+    ) -> ProviderResult<ForecastWeather> {
+        let mut weather = ForecastWeather::new();
+        for (i, date) in start
+            .iter_days()
+            .take_while(|date| *date <= end)
+            .enumerate()
+        {
+            let day = self.history(query, date, lang).await?;
+            if i == 0 {
+                weather.location = day.location;
+            }
+            if day.stale_since.is_some() {
+                weather.stale_since = day.stale_since;
+            }
+            weather
+                .forecast
+                .forecastday
+                .extend(day.forecast.forecastday);
+        }
+        Ok(weather)
+    }
+
+    /// Fetch the sun and moon data of a day together with the day before
+    ///
+    /// The day before is needed for the change of the day length.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The location to get the data for
+    /// * `date` - The day to get the data for, today at the location if not
+    ///   given
+    /// * `lang` - The language code for the location names, if supported
+    ///
+    /// # Errors
+    ///
+    /// If one of the days cannot be fetched
+    pub async fn astronomy_with_previous_day(
+        &self,
+        query: &LocationQuery,
+        date: Option<NaiveDate>,
+        lang: &str,
+    ) -> ProviderResult<Astronomy> {
+        let (date, mut astronomy) = match date {
+            Some(date) => (date, self.astronomy(query, date, lang).await?),
+            None => {
+                // The location is only known after the first request, its
+                // date differs from the UTC date by at most a day
+                let now = Utc::now();
+                let guess = now.date_naive();
+                let astronomy = self.astronomy(query, guess, lang).await?;
+                match astronomy.location.date_at(now) {
+                    Some(today) if today != guess && (today - guess).num_days().abs() == 1 => {
+                        (today, self.astronomy(query, today, lang).await?)
+                    }
+                    _ => (guess, astronomy),
+                }
+            }
+        };
+        if let Some(previous) = date.pred_opt() {
+            astronomy.previous_astro = Some(self.astronomy(query, previous, lang).await?.astro);
+        }
+        Ok(astronomy)
+    }
+
+    /// Build the cache key of a request
+    ///
+    /// The key contains the base URL override, so responses of a mock server
+    /// are never served for the real endpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The kind of request, e.g. `forecast`
+    /// * `query` - The normalized location query
+    /// * `lang` - The language code of the request
+    /// * `params` - Further parameters that change the response
+    fn cache_key(&self, endpoint: &str, query: &str, lang: &str, params: &[String]) -> String {
+        format!(
+            "{}|{}|{}|{}|{}|{}",
+            self.kind,
+            self.base_url.as_deref().unwrap_or_default(),
+            endpoint,
+            query,
+            lang,
+            params.join(",")
+        )
+    }
+
+    /// Serve a request from the cache or fetch and store it
+    ///
+    /// Fresh entries are served without a request. If fetching fails
+    /// because the network or the provider is unavailable, an expired entry
+    /// is served instead and marked as stale. In offline mode every entry is
+    /// served regardless of its age and nothing is fetched.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The cache key of the request
+    /// * `ttl` - How long entries are considered fresh
+    /// * `fetch` - The request to send if the cache cannot answer it
+    ///
+    /// # Errors
+    ///
+    /// If the request fails and there is no cached entry, or there is no
+    /// cached entry in offline mode
+    async fn cached<T: Cacheable>(
+        &self,
+        key: String,
+        ttl: Duration,
+        fetch: impl Future<Output = ProviderResult<T>>,
+    ) -> ProviderResult<T> {
+        let Some(cache) = self.cache.as_ref() else {
+            if self.offline {
+                return Err(WeatherError::NotCached);
+            }
+            return fetch.await;
+        };
+        let cached = match cache.mode() {
+            CacheMode::Disabled => return fetch.await,
+            CacheMode::Offline => {
+                return cache
+                    .get(&key, ttl)
+                    .map(CachedValue::into_data)
+                    .ok_or(WeatherError::NotCached);
+            }
+            CacheMode::Normal => match cache.get::<T>(&key, ttl) {
+                Some(cached) if !cached.expired => return Ok(cached.data),
+                cached => cached,
+            },
+        };
+
+        match fetch.await {
+            Ok(data) => {
+                cache.put(&key, &data);
+                Ok(data)
+            }
+            Err(e) if e.is_transient() => cached.map(CachedValue::into_data).ok_or(e),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl WeatherProvider for Provider {
+    async fn current(
+        &self,
+        query: &LocationQuery,
+        lang: &str,
+        options: &FetchOptions,
+    ) -> ProviderResult<Weather> {
+        let key = self.cache_key(
+            "current",
+            &normalize_query(query),
+            lang,
+            &[format!("aqi={}", options.air_quality)],
+        );
+        let fetch = async {
+            match &self.backend {
+                Backend::WeatherApi(provider) => provider.current(query, lang, options).await,
+                Backend::OpenMeteo(provider) => provider.current(query, lang, options).await,
+            }
+        };
+        self.cached(key, CURRENT_TTL, fetch).await
+    }
+
+    async fn forecast(
+        &self,
+        query: &LocationQuery,
+        days: usize,
+        lang: &str,
+        options: &FetchOptions,
+    ) -> ProviderResult<ForecastWeather> {
+        let key = self.cache_key(
+            "forecast",
+            &normalize_query(query),
+            lang,
+            &[
+                format!("days={}", days),
+                format!("aqi={}", options.air_quality),
+                format!("alerts={}", options.alerts),
+            ],
+        );
+        // Alerts have to be as current as the current weather
+        let ttl = if options.alerts {
+            CURRENT_TTL
+        } else {
+            FORECAST_TTL
+        };
+        let fetch = async {
+            match &self.backend {
+                Backend::WeatherApi(provider) => {
+                    provider.forecast(query, days, lang, options).await
+                }
+                Backend::OpenMeteo(provider) => provider.forecast(query, days, lang, options).await,
+            }
+        };
+        self.cached(key, ttl, fetch).await
+    }
+
+    async fn history(
+        &self,
+        query: &LocationQuery,
+        date: NaiveDate,
+        lang: &str,
+    ) -> ProviderResult<ForecastWeather> {
+        let key = self.cache_key(
+            "history",
+            &normalize_query(query),
+            lang,
+            &[format!("date={}", date)],
+        );
+        let fetch = async {
+            match &self.backend {
+                Backend::WeatherApi(provider) => provider.history(query, date, lang).await,
+                Backend::OpenMeteo(provider) => provider.history(query, date, lang).await,
+            }
+        };
+        self.cached(key, HISTORY_TTL, fetch).await
+    }
+
+    async fn astronomy(
+        &self,
+        query: &LocationQuery,
+        date: NaiveDate,
+        lang: &str,
+    ) -> ProviderResult<Astronomy> {
+        let key = self.cache_key(
+            "astronomy",
+            &normalize_query(query),
+            lang,
+            &[format!("date={}", date)],
+        );
+        let fetch = async {
+            match &self.backend {
+                Backend::WeatherApi(provider) => provider.astronomy(query, date, lang).await,
+                Backend::OpenMeteo(provider) => provider.astronomy(query, date, lang).await,
+            }
+        };
+        self.cached(key, ASTRONOMY_TTL, fetch).await
+    }
+
+    async fn search(&self, query: &str, lang: &str) -> ProviderResult<Vec<SearchLocation>> {
+        let key = self.cache_key("search", &normalize_name(query), lang, &[]);
+        let fetch = async {
+            match &self.backend {
+                Backend::WeatherApi(provider) => provider.search(query, lang).await,
+                Backend::OpenMeteo(provider) => provider.search(query, lang).await,
+            }
+        };
+        self.cached(key, SEARCH_TTL, fetch).await
+    }
+}
+
+/// Normalize a location query for the cache key
+///
+/// Names are compared case-insensitively with collapsed whitespace and
+/// coordinates are rounded to about 10 m.
+fn normalize_query(query: &LocationQuery) -> String {
+    match query {
+        LocationQuery::Coordinates { lat, lon } => format!("{:.4},{:.4}", lat, lon),
+        LocationQuery::City(name) => normalize_name(name),
+        _ => normalize_name(&query.to_string()),
+    }
+}
+
+/// Lowercase a name and collapse its whitespace
+fn normalize_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::client::HttpConfig;
+
+    fn provider(kind: ProviderKind, base_url: Option<&str>) -> Provider {
+        let client = HttpClient::new(HttpConfig {
+            base_url: base_url.map(str::to_string),
+            ..Default::default()
+        })
+        .unwrap();
+        Provider::new(kind, client)
+    }
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "rusty-weather-provider-{}-{}",
+            name,
+            std::process::id()
+        ));
+        std::fs::remove_dir_all(&dir).ok();
+        dir
+    }
+
+    fn cached_provider(name: &str, mode: CacheMode) -> Provider {
+        provider(ProviderKind::OpenMeteo, None)
+            .with_cache(Some(Cache::with_dir(cache_dir(name), mode)))
+    }
+
+    fn weather(name: &str) -> Weather {
+        let mut weather = Weather::new();
+        weather.location.name = name.to_string();
+        weather
+    }
+
+    fn unavailable() -> WeatherError {
+        WeatherError::HttpStatus(503)
+    }
+
+    #[tokio::test]
+    async fn fresh_entries_are_served_without_fetching() {
+        let provider = cached_provider("fresh", CacheMode::Normal);
+        let ttl = Duration::from_secs(60);
+
+        let first = provider
+            .cached("key".to_string(), ttl, async { Ok(weather("Berlin")) })
+            .await
+            .unwrap();
+        let second = provider
+            .cached::<Weather>("key".to_string(), ttl, async {
+                panic!("the cache should answer")
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(first.location.name, "Berlin");
+        assert_eq!(second.location.name, "Berlin");
+        assert!(second.stale_since.is_none());
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_refetched() {
+        let provider = cached_provider("refetch", CacheMode::Normal);
+        provider
+            .cached("key".to_string(), Duration::ZERO, async {
+                Ok(weather("Berlin"))
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+        let weather = provider
+            .cached("key".to_string(), Duration::ZERO, async {
+                Ok(weather("Paris"))
+            })
+            .await
+            .unwrap();
+        assert_eq!(weather.location.name, "Paris");
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_served_stale_on_transient_errors() {
+        let provider = cached_provider("stale", CacheMode::Normal);
+        provider
+            .cached("key".to_string(), Duration::ZERO, async {
+                Ok(weather("Berlin"))
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+        let stale = provider
+            .cached::<Weather>("key".to_string(), Duration::ZERO, async {
+                Err(unavailable())
+            })
+            .await
+            .unwrap();
+        assert_eq!(stale.location.name, "Berlin");
+        assert!(stale.stale_since.is_some());
+
+        // Other errors are not hidden by the cache
+        let error = provider
+            .cached::<Weather>("key".to_string(), Duration::ZERO, async {
+                Err(WeatherError::UnknownLocation("Berlin".to_string()))
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(error, WeatherError::UnknownLocation(_)));
+    }
+
+    #[tokio::test]
+    async fn transient_errors_without_an_entry_are_returned() {
+        let provider = cached_provider("no-entry", CacheMode::Normal);
+
+        let error = provider
+            .cached::<Weather>("key".to_string(), Duration::ZERO, async {
+                Err(unavailable())
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(error, WeatherError::HttpStatus(503)));
+    }
+
+    #[tokio::test]
+    async fn offline_mode_never_fetches() {
+        let dir = cache_dir("offline");
+        let online = provider(ProviderKind::OpenMeteo, None)
+            .with_cache(Some(Cache::with_dir(dir.clone(), CacheMode::Normal)));
+        online
+            .cached("key".to_string(), Duration::ZERO, async {
+                Ok(weather("Berlin"))
+            })
+            .await
+            .unwrap();
+
+        let offline = provider(ProviderKind::OpenMeteo, None)
+            .with_cache(Some(Cache::with_dir(dir, CacheMode::Offline)))
+            .with_offline(true);
+        let cached = offline
+            .cached::<Weather>("key".to_string(), Duration::ZERO, async {
+                panic!("offline mode must not fetch")
+            })
+            .await
+            .unwrap();
+        assert_eq!(cached.location.name, "Berlin");
+
+        let missing = offline
+            .cached::<Weather>("other".to_string(), Duration::ZERO, async {
+                panic!("offline mode must not fetch")
+            })
+            .await;
+        assert!(matches!(missing, Err(WeatherError::NotCached)));
+    }
+
+    #[tokio::test]
+    async fn offline_mode_without_a_cache_is_not_cached() {
+        let provider = provider(ProviderKind::OpenMeteo, None).with_offline(true);
+
+        let result = provider
+            .cached::<Weather>("key".to_string(), Duration::ZERO, async {
+                panic!("offline mode must not fetch")
+            })
+            .await;
+        assert!(matches!(result, Err(WeatherError::NotCached)));
+    }
+
+    #[test]
+    fn cache_keys_separate_every_part_of_a_request() {
+        let open_meteo = provider(ProviderKind::OpenMeteo, None);
+        let key = |provider: &Provider, endpoint, query, params: &[&str]| {
+            let params: Vec<String> = params.iter().map(|param| param.to_string()).collect();
+            provider.cache_key(endpoint, query, "en", &params)
+        };
+        let base = key(&open_meteo, "forecast", "berlin", &["days=3"]);
+
+        assert_ne!(
+            base,
+            key(
+                &provider(ProviderKind::WeatherApi, None),
+                "forecast",
+                "berlin",
+                &["days=3"]
+            )
+        );
+        assert_ne!(
+            base,
+            key(
+                &provider(ProviderKind::OpenMeteo, Some("http://127.0.0.1:8765")),
+                "forecast",
+                "berlin",
+                &["days=3"]
+            )
+        );
+        assert_ne!(base, key(&open_meteo, "history", "berlin", &["days=3"]));
+        assert_ne!(base, key(&open_meteo, "forecast", "paris", &["days=3"]));
+        assert_ne!(base, key(&open_meteo, "forecast", "berlin", &["days=5"]));
+        assert_ne!(
+            base,
+            open_meteo.cache_key("forecast", "berlin", "de", &["days=3".to_string()])
+        );
+        assert_eq!(base, key(&open_meteo, "forecast", "berlin", &["days=3"]));
+    }
+
+    #[test]
+    fn queries_are_normalized_for_the_cache_key() {
+        assert_eq!(
+            normalize_query(&LocationQuery::City("  New   York ".to_string())),
+            "new york"
+        );
+        assert_eq!(
+            normalize_query(&LocationQuery::Coordinates {
+                lat: 52.520_006,
+                lon: 13.404_954
+            }),
+            "52.5200,13.4050"
+        );
+    }
+
+    #[test]
+    fn debug_output_hides_the_api_key() {
+        let provider = Provider::new(
+            ProviderKind::WeatherApi,
+            HttpClient::new(HttpConfig::default()).unwrap(),
+        )
+        .with_api_key(Some("secret-key-123".to_string()));
+
+        let output = format!("{:?}", provider);
+        assert!(!output.contains("secret-key-123"));
+        assert!(output.contains("REDACTED"));
+    }
+}
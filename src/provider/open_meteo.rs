@@ -0,0 +1,1106 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime, Utc};
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use super::{FetchOptions, ProviderResult, WeatherProvider};
+use crate::client::HttpClient;
+use crate::error::WeatherError;
+use crate::location::LocationQuery;
+use crate::units::{
+    c_to_f, degrees_to_compass, km_to_miles, kph_to_mph, mb_to_in, mm_to_in, round1,
+};
+use crate::utils::translate;
+use crate::weather::{
+    AirQuality, Astro, Astronomy, Day, ForecastDay, ForecastWeather, Hour, SearchLocation, Weather,
+    WeatherCondition, WeatherCurrent, WeatherForecast, WeatherLocation,
+};
+
+const FORECAST_URL: &str = "https://api.open-meteo.com/v1";
+const GEOCODING_URL: &str = "https://geocoding-api.open-meteo.com/v1";
+const AIR_QUALITY_URL: &str = "https://air-quality-api.open-meteo.com/v1";
+const ARCHIVE_URL: &str = "https://archive-api.open-meteo.com/v1";
+
+const CURRENT_FIELDS: &str = "temperature_2m,relative_humidity_2m,apparent_temperature,is_day,\
+precipitation,weather_code,cloud_cover,pressure_msl,wind_speed_10m,wind_direction_10m,\
+wind_gusts_10m,visibility,uv_index";
+const HOURLY_FIELDS: &str = "temperature_2m,relative_humidity_2m,dew_point_2m,\
+apparent_temperature,precipitation_probability,precipitation,rain,snowfall,weather_code,\
+pressure_msl,cloud_cover,visibility,wind_speed_10m,wind_direction_10m,wind_gusts_10m,uv_index,\
+is_day";
+const AIR_QUALITY_FIELDS: &str =
+    "carbon_monoxide,nitrogen_dioxide,ozone,sulphur_dioxide,pm2_5,pm10,us_aqi";
+const DAILY_FIELDS: &str = "weather_code,temperature_2m_max,temperature_2m_min,sunrise,sunset,\
+uv_index_max,precipitation_sum,rain_sum,snowfall_sum,precipitation_probability_max,\
+wind_speed_10m_max";
+/// The archive has no forecast-only fields like the chance of rain, the
+/// visibility or the UV index
+const ARCHIVE_HOURLY_FIELDS: &str = "temperature_2m,relative_humidity_2m,dew_point_2m,\
+apparent_temperature,precipitation,rain,snowfall,weather_code,pressure_msl,cloud_cover,\
+wind_speed_10m,wind_direction_10m,wind_gusts_10m,is_day";
+const ARCHIVE_DAILY_FIELDS: &str = "weather_code,temperature_2m_max,temperature_2m_min,sunrise,\
+sunset,precipitation_sum,rain_sum,snowfall_sum,wind_speed_10m_max";
+
+/// The air quality API only forecasts this many days
+const AIR_QUALITY_DAYS: usize = 7;
+/// Length of the synodic month in days
+const SYNODIC_MONTH: f64 = 29.530588853;
+
+/// Weather provider backed by [Open-Meteo](https://open-meteo.com/)
+///
+/// Open-Meteo needs no API key. Locations are resolved through its geocoding
+/// API unless the query already is a `lat,lon` pair.
+#[derive(Debug, Clone)]
+pub struct OpenMeteo {
+    client: HttpClient,
+    forecast_url: String,
+    geocoding_url: String,
+    air_quality_url: String,
+    archive_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MeteoError {
+    reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeocodingResponse {
+    #[serde(default)]
+    results: Vec<GeocodingResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeocodingResult {
+    id: i64,
+    name: String,
+    latitude: f64,
+    longitude: f64,
+    admin1: Option<String>,
+    country: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MeteoResponse {
+    timezone: String,
+    utc_offset_seconds: i64,
+    current: Option<MeteoCurrent>,
+    hourly: Option<MeteoHourly>,
+    daily: Option<MeteoDaily>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MeteoCurrent {
+    time: String,
+    temperature_2m: Option<f64>,
+    relative_humidity_2m: Option<f64>,
+    apparent_temperature: Option<f64>,
+    is_day: Option<i64>,
+    precipitation: Option<f64>,
+    weather_code: Option<i64>,
+    cloud_cover: Option<f64>,
+    pressure_msl: Option<f64>,
+    wind_speed_10m: Option<f64>,
+    wind_direction_10m: Option<f64>,
+    wind_gusts_10m: Option<f64>,
+    visibility: Option<f64>,
+    uv_index: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MeteoHourly {
+    time: Vec<String>,
+    temperature_2m: Vec<Option<f64>>,
+    relative_humidity_2m: Vec<Option<f64>>,
+    dew_point_2m: Vec<Option<f64>>,
+    apparent_temperature: Vec<Option<f64>>,
+    #[serde(default)]
+    precipitation_probability: Vec<Option<f64>>,
+    precipitation: Vec<Option<f64>>,
+    rain: Vec<Option<f64>>,
+    snowfall: Vec<Option<f64>>,
+    weather_code: Vec<Option<i64>>,
+    pressure_msl: Vec<Option<f64>>,
+    cloud_cover: Vec<Option<f64>>,
+    #[serde(default)]
+    visibility: Vec<Option<f64>>,
+    wind_speed_10m: Vec<Option<f64>>,
+    wind_direction_10m: Vec<Option<f64>>,
+    wind_gusts_10m: Vec<Option<f64>>,
+    #[serde(default)]
+    uv_index: Vec<Option<f64>>,
+    is_day: Vec<Option<i64>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AirQualityResponse {
+    current: Option<AirQualityCurrent>,
+    hourly: Option<AirQualityHourly>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AirQualityCurrent {
+    carbon_monoxide: Option<f64>,
+    nitrogen_dioxide: Option<f64>,
+    ozone: Option<f64>,
+    sulphur_dioxide: Option<f64>,
+    pm2_5: Option<f64>,
+    pm10: Option<f64>,
+    us_aqi: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AirQualityHourly {
+    time: Vec<String>,
+    carbon_monoxide: Vec<Option<f64>>,
+    nitrogen_dioxide: Vec<Option<f64>>,
+    ozone: Vec<Option<f64>>,
+    sulphur_dioxide: Vec<Option<f64>>,
+    pm2_5: Vec<Option<f64>>,
+    pm10: Vec<Option<f64>>,
+    us_aqi: Vec<Option<f64>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MeteoDaily {
+    time: Vec<String>,
+    weather_code: Vec<Option<i64>>,
+    temperature_2m_max: Vec<Option<f64>>,
+    temperature_2m_min: Vec<Option<f64>>,
+    sunrise: Vec<Option<String>>,
+    sunset: Vec<Option<String>>,
+    #[serde(default)]
+    uv_index_max: Vec<Option<f64>>,
+    precipitation_sum: Vec<Option<f64>>,
+    rain_sum: Vec<Option<f64>>,
+    snowfall_sum: Vec<Option<f64>>,
+    #[serde(default)]
+    precipitation_probability_max: Vec<Option<f64>>,
+    wind_speed_10m_max: Vec<Option<f64>>,
+}
+
+impl OpenMeteo {
+    /// Create a new Open-Meteo provider
+    ///
+    /// If the client has a base URL override, the forecast (`/forecast`), the
+    /// geocoding (`/search`), the air quality (`/air-quality`) and the
+    /// archive (`/archive`) endpoints are expected below it, which makes it
+    /// easy to point the provider at a local stub server.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The HTTP client to send the requests with
+    pub fn new(client: HttpClient) -> Self {
+        let base_url = client.base_url().map(str::to_string);
+        let url = |default: &str| base_url.clone().unwrap_or_else(|| default.to_string());
+        OpenMeteo {
+            forecast_url: url(FORECAST_URL),
+            geocoding_url: url(GEOCODING_URL),
+            air_quality_url: url(AIR_QUALITY_URL),
+            archive_url: url(ARCHIVE_URL),
+            client,
+        }
+    }
+
+    /// Send a GET request and return the response body
+    ///
+    /// # Errors
+    ///
+    /// If the request fails or the server does not answer with a success status
+    async fn fetch(&self, url: &str, params: &[(&str, String)]) -> ProviderResult<String> {
+        let resp = self.client.get(url, params).await?;
+        let status = resp.status();
+        if status.is_success() {
+            return Ok(resp.text().await?);
+        }
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            return Err(WeatherError::QuotaExceeded);
+        }
+
+        // Open-Meteo explains rejected requests as `{"error":true,"reason":"..."}`
+        match serde_json::from_str::<MeteoError>(&resp.text().await?) {
+            Ok(error) => Err(WeatherError::Provider {
+                code: i64::from(status.as_u16()),
+                message: error.reason,
+            }),
+            Err(_) => Err(WeatherError::HttpStatus(status.as_u16())),
+        }
+    }
+
+    /// Resolve a query into a location and its coordinates
+    ///
+    /// Coordinates are used as they are, names and postcodes are looked up
+    /// through the geocoding API and the best match is used.
+    ///
+    /// # Errors
+    ///
+    /// If the geocoding request fails, no location matches the query or the
+    /// kind of query is not supported by Open-Meteo
+    async fn resolve(&self, query: &LocationQuery, lang: &str) -> ProviderResult<SearchLocation> {
+        let name = match query {
+            LocationQuery::Coordinates { lat, lon } => {
+                return Ok(SearchLocation {
+                    id: 0,
+                    name: query.to_string(),
+                    region: String::new(),
+                    country: String::new(),
+                    lat: *lat,
+                    lon: *lon,
+                });
+            }
+            LocationQuery::City(name) | LocationQuery::Zip(name) => name,
+            LocationQuery::Iata(_) | LocationQuery::Metar(_) | LocationQuery::AutoIp => {
+                return Err(WeatherError::UnsupportedQuery(query.to_string()));
+            }
+        };
+
+        self.geocode(name, lang, 1)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| WeatherError::UnknownLocation(query.to_string()))
+    }
+
+    /// Look up locations by name through the geocoding API
+    async fn geocode(
+        &self,
+        name: &str,
+        lang: &str,
+        count: usize,
+    ) -> ProviderResult<Vec<SearchLocation>> {
+        let body = self
+            .fetch(
+                &format!("{}/search", self.geocoding_url),
+                &[
+                    ("name", name.to_string()),
+                    ("count", count.to_string()),
+                    ("language", lang.to_string()),
+                    ("format", "json".to_string()),
+                ],
+            )
+            .await?;
+        let data: GeocodingResponse = serde_json::from_str(&body)?;
+
+        Ok(data.results.into_iter().map(Into::into).collect())
+    }
+
+    /// Request the forecast endpoint for a resolved location
+    async fn fetch_forecast(
+        &self,
+        location: &SearchLocation,
+        params: &[(&str, String)],
+    ) -> ProviderResult<MeteoResponse> {
+        let mut query = vec![
+            ("latitude", location.lat.to_string()),
+            ("longitude", location.lon.to_string()),
+            ("timezone", "auto".to_string()),
+        ];
+        query.extend(params.iter().cloned());
+
+        let body = self
+            .fetch(&format!("{}/forecast", self.forecast_url), &query)
+            .await?;
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    /// Request the historical weather archive for a resolved location
+    async fn fetch_archive(
+        &self,
+        location: &SearchLocation,
+        params: &[(&str, String)],
+    ) -> ProviderResult<MeteoResponse> {
+        let mut query = vec![
+            ("latitude", location.lat.to_string()),
+            ("longitude", location.lon.to_string()),
+            ("timezone", "auto".to_string()),
+        ];
+        query.extend(params.iter().cloned());
+
+        let body = self
+            .fetch(&format!("{}/archive", self.archive_url), &query)
+            .await?;
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    /// Request the air quality endpoint for a resolved location
+    async fn fetch_air_quality(
+        &self,
+        location: &SearchLocation,
+        params: &[(&str, String)],
+    ) -> ProviderResult<AirQualityResponse> {
+        let mut query = vec![
+            ("latitude", location.lat.to_string()),
+            ("longitude", location.lon.to_string()),
+            ("timezone", "auto".to_string()),
+        ];
+        query.extend(params.iter().cloned());
+
+        let body = self
+            .fetch(&format!("{}/air-quality", self.air_quality_url), &query)
+            .await?;
+        Ok(serde_json::from_str(&body)?)
+    }
+}
+
+impl WeatherProvider for OpenMeteo {
+    async fn current(
+        &self,
+        query: &LocationQuery,
+        lang: &str,
+        options: &FetchOptions,
+    ) -> ProviderResult<Weather> {
+        let location = self.resolve(query, lang).await?;
+        let data = self
+            .fetch_forecast(&location, &[("current", CURRENT_FIELDS.to_string())])
+            .await?;
+        let current = data
+            .current
+            .as_ref()
+            .ok_or_else(|| WeatherError::Decode("missing current weather".to_string()))?;
+        let mut weather_current = to_weather_current(current, data.utc_offset_seconds);
+
+        if options.air_quality {
+            let air_quality = self
+                .fetch_air_quality(&location, &[("current", AIR_QUALITY_FIELDS.to_string())])
+                .await?;
+            weather_current.air_quality = air_quality.current.map(|current| {
+                to_air_quality(
+                    [
+                        current.carbon_monoxide,
+                        current.nitrogen_dioxide,
+                        current.ozone,
+                        current.sulphur_dioxide,
+                        current.pm2_5,
+                        current.pm10,
+                    ],
+                    current.us_aqi,
+                )
+            });
+        }
+
+        Ok(Weather {
+            location: to_weather_location(location, &data),
+            current: weather_current,
+            stale_since: None,
+        })
+    }
+
+    async fn forecast(
+        &self,
+        query: &LocationQuery,
+        days: usize,
+        lang: &str,
+        options: &FetchOptions,
+    ) -> ProviderResult<ForecastWeather> {
+        if options.alerts {
+            return Err(WeatherError::UnsupportedFeature("alerts".to_string()));
+        }
+        let location = self.resolve(query, lang).await?;
+        let data = self
+            .fetch_forecast(
+                &location,
+                &[
+                    ("hourly", HOURLY_FIELDS.to_string()),
+                    ("daily", DAILY_FIELDS.to_string()),
+                    ("forecast_days", days.to_string()),
+                ],
+            )
+            .await?;
+        let mut forecastday = to_forecast_days(&data)?;
+
+        let air_quality = if options.air_quality {
+            self.fetch_air_quality(
+                &location,
+                &[
+                    ("hourly", AIR_QUALITY_FIELDS.to_string()),
+                    ("forecast_days", days.min(AIR_QUALITY_DAYS).to_string()),
+                ],
+            )
+            .await?
+            .hourly
+        } else {
+            None
+        };
+
+        if let Some(hourly) = &air_quality {
+            for forecast_day in &mut forecastday {
+                forecast_day.day.air_quality = to_daily_air_quality(hourly, &forecast_day.date);
+            }
+        }
+
+        Ok(ForecastWeather {
+            location: to_weather_location(location, &data),
+            forecast: WeatherForecast { forecastday },
+            alerts: Vec::new(),
+            stale_since: None,
+        })
+    }
+
+    async fn history(
+        &self,
+        query: &LocationQuery,
+        date: NaiveDate,
+        lang: &str,
+    ) -> ProviderResult<ForecastWeather> {
+        let location = self.resolve(query, lang).await?;
+        let date = date.format("%Y-%m-%d").to_string();
+        let data = self
+            .fetch_archive(
+                &location,
+                &[
+                    ("hourly", ARCHIVE_HOURLY_FIELDS.to_string()),
+                    ("daily", ARCHIVE_DAILY_FIELDS.to_string()),
+                    ("start_date", date.clone()),
+                    ("end_date", date),
+                ],
+            )
+            .await?;
+        let forecastday = to_forecast_days(&data)?;
+
+        Ok(ForecastWeather {
+            location: to_weather_location(location, &data),
+            forecast: WeatherForecast { forecastday },
+            alerts: Vec::new(),
+            stale_since: None,
+        })
+    }
+
+    async fn astronomy(
+        &self,
+        query: &LocationQuery,
+        date: NaiveDate,
+        lang: &str,
+    ) -> ProviderResult<Astronomy> {
+        let location = self.resolve(query, lang).await?;
+        let date = date.format("%Y-%m-%d").to_string();
+        let data = self
+            .fetch_forecast(
+                &location,
+                &[
+                    ("daily", DAILY_FIELDS.to_string()),
+                    ("start_date", date.clone()),
+                    ("end_date", date.clone()),
+                ],
+            )
+            .await?;
+        let daily = data
+            .daily
+            .as_ref()
+            .ok_or_else(|| WeatherError::Decode("missing daily forecast".to_string()))?;
+        let mut astro = to_astro(daily, 0);
+        let location = to_weather_location(location, &data);
+
+        // Whether the sun is up only makes sense for today
+        if let (Some(today), Some(now)) = (
+            location.localtime.split(' ').next(),
+            location.localtime.split(' ').nth(1),
+        ) && today == date
+        {
+            astro.is_sun_up =
+                i64::from(astro.sunrise.as_str() <= now && now < astro.sunset.as_str());
+        }
+
+        Ok(Astronomy {
+            location,
+            date,
+            astro,
+            previous_astro: None,
+            stale_since: None,
+        })
+    }
+
+    async fn search(&self, query: &str, lang: &str) -> ProviderResult<Vec<SearchLocation>> {
+        self.geocode(query, lang, 10).await
+    }
+}
+
+impl From<GeocodingResult> for SearchLocation {
+    fn from(result: GeocodingResult) -> Self {
+        SearchLocation {
+            id: result.id,
+            name: result.name,
+            region: result.admin1.unwrap_or_default(),
+            country: result.country.unwrap_or_default(),
+            lat: result.latitude,
+            lon: result.longitude,
+        }
+    }
+}
+
+fn to_weather_location(location: SearchLocation, data: &MeteoResponse) -> WeatherLocation {
+    let now = Utc::now();
+    let local = now.naive_utc() + Duration::seconds(data.utc_offset_seconds);
+
+    WeatherLocation {
+        name: location.name,
+        region: location.region,
+        country: location.country,
+        lat: location.lat,
+        lon: location.lon,
+        tz_id: data.timezone.clone(),
+        localtime_epoch: now.timestamp(),
+        localtime: local.format("%Y-%m-%d %H:%M").to_string(),
+    }
+}
+
+fn to_weather_current(current: &MeteoCurrent, utc_offset: i64) -> WeatherCurrent {
+    let temp_c = current.temperature_2m.unwrap_or_default();
+    let feelslike_c = current.apparent_temperature.unwrap_or_default();
+    let wind_kph = current.wind_speed_10m.unwrap_or_default();
+    let gust_kph = current.wind_gusts_10m.unwrap_or_default();
+    let wind_degree = current.wind_direction_10m.unwrap_or_default();
+    let pressure_mb = current.pressure_msl.unwrap_or_default();
+    let precip_mm = current.precipitation.unwrap_or_default();
+    let vis_km = current.visibility.unwrap_or_default() / 1000.0;
+    let code = current.weather_code.unwrap_or_default();
+
+    WeatherCurrent {
+        last_updated_epoch: local_to_epoch(&current.time, utc_offset),
+        last_updated: current.time.replace('T', " "),
+        temp_c,
+        temp_f: c_to_f(temp_c),
+        is_day: current.is_day.unwrap_or(1),
+        condition: to_condition(code),
+        wind_mph: kph_to_mph(wind_kph),
+        wind_kph,
+        wind_degree: wind_degree.round() as i64,
+        wind_dir: degrees_to_compass(wind_degree),
+        pressure_mb,
+        pressure_in: mb_to_in(pressure_mb),
+        precip_mm,
+        precip_in: mm_to_in(precip_mm),
+        humidity: current.relative_humidity_2m.unwrap_or_default().round() as i64,
+        cloud: current.cloud_cover.unwrap_or_default().round() as i64,
+        feelslike_c,
+        feelslike_f: c_to_f(feelslike_c),
+        vis_km,
+        vis_miles: km_to_miles(vis_km),
+        uv: current.uv_index.unwrap_or_default(),
+        gust_mph: kph_to_mph(gust_kph),
+        gust_kph,
+        air_quality: None,
+    }
+}
+
+fn to_hour(hourly: &MeteoHourly, i: usize) -> Hour {
+    let value = |values: &[Option<f64>]| values.get(i).copied().flatten().unwrap_or_default();
+    let temp_c = value(&hourly.temperature_2m);
+    let feelslike_c = value(&hourly.apparent_temperature);
+    let dewpoint_c = value(&hourly.dew_point_2m);
+    let wind_kph = value(&hourly.wind_speed_10m);
+    let gust_kph = value(&hourly.wind_gusts_10m);
+    let wind_degree = value(&hourly.wind_direction_10m);
+    let pressure_mb = value(&hourly.pressure_msl);
+    let precip_mm = value(&hourly.precipitation);
+    let rain_mm = value(&hourly.rain);
+    let snow_cm = value(&hourly.snowfall);
+    let chance = value(&hourly.precipitation_probability).round() as i64;
+    let vis_km = value(&hourly.visibility) / 1000.0;
+    let code = hourly
+        .weather_code
+        .get(i)
+        .copied()
+        .flatten()
+        .unwrap_or_default();
+
+    Hour {
+        time: hourly.time[i].replace('T', " "),
+        temp_c,
+        temp_f: c_to_f(temp_c),
+        is_day: hourly.is_day.get(i).copied().flatten().unwrap_or(1),
+        condition: to_condition(code),
+        wind_mph: kph_to_mph(wind_kph),
+        wind_kph,
+        wind_degree: wind_degree.round() as i64,
+        wind_dir: degrees_to_compass(wind_degree),
+        pressure_mb,
+        pressure_in: mb_to_in(pressure_mb),
+        precip_mm,
+        precip_in: mm_to_in(precip_mm),
+        snow_cm,
+        humidity: value(&hourly.relative_humidity_2m).round() as i64,
+        cloud: value(&hourly.cloud_cover).round() as i64,
+        feelslike_c,
+        feelslike_f: c_to_f(feelslike_c),
+        // Open-Meteo only offers the apparent temperature, which covers both
+        windchill_c: feelslike_c,
+        windchill_f: c_to_f(feelslike_c),
+        heatindex_c: feelslike_c,
+        heatindex_f: c_to_f(feelslike_c),
+        dewpoint_c,
+        dewpoint_f: c_to_f(dewpoint_c),
+        will_it_rain: i64::from(rain_mm > 0.0),
+        chance_of_rain: if snow_cm > 0.0 && rain_mm == 0.0 {
+            0
+        } else {
+            chance
+        },
+        will_it_snow: i64::from(snow_cm > 0.0),
+        chance_of_snow: if snow_cm > 0.0 { chance } else { 0 },
+        vis_km,
+        vis_miles: km_to_miles(vis_km),
+        gust_mph: kph_to_mph(gust_kph),
+        gust_kph,
+        uv: value(&hourly.uv_index),
+    }
+}
+
+/// Build the days of a forecast or archive response with their hours
+///
+/// # Errors
+///
+/// If the response has no hourly or no daily data
+fn to_forecast_days(data: &MeteoResponse) -> ProviderResult<Vec<ForecastDay>> {
+    let hourly = data
+        .hourly
+        .as_ref()
+        .ok_or_else(|| WeatherError::Decode("missing hourly forecast".to_string()))?;
+    let daily = data
+        .daily
+        .as_ref()
+        .ok_or_else(|| WeatherError::Decode("missing daily forecast".to_string()))?;
+    let hours: Vec<Hour> = (0..hourly.time.len()).map(|i| to_hour(hourly, i)).collect();
+
+    Ok((0..daily.time.len())
+        .map(|i| {
+            let date = daily.time[i].clone();
+            let day_hours: Vec<Hour> = hours
+                .iter()
+                .filter(|hour| hour.time.starts_with(&date))
+                .cloned()
+                .collect();
+            ForecastDay {
+                day: to_day(daily, i, &day_hours),
+                astro: to_astro(daily, i),
+                hour: day_hours,
+                date,
+            }
+        })
+        .collect())
+}
+
+fn to_day(daily: &MeteoDaily, i: usize, hours: &[Hour]) -> Day {
+    let value = |values: &[Option<f64>]| values.get(i).copied().flatten().unwrap_or_default();
+    let average = |f: fn(&Hour) -> f64| {
+        if hours.is_empty() {
+            0.0
+        } else {
+            hours.iter().map(f).sum::<f64>() / hours.len() as f64
+        }
+    };
+    let maxtemp_c = value(&daily.temperature_2m_max);
+    let mintemp_c = value(&daily.temperature_2m_min);
+    let avgtemp_c = round1(average(|hour| hour.temp_c));
+    let maxwind_kph = value(&daily.wind_speed_10m_max);
+    let totalprecip_mm = value(&daily.precipitation_sum);
+    let rain_mm = value(&daily.rain_sum);
+    let snow_cm = value(&daily.snowfall_sum);
+    let chance = value(&daily.precipitation_probability_max).round() as i64;
+    let avgvis_km = round1(average(|hour| hour.vis_km));
+    let code = daily
+        .weather_code
+        .get(i)
+        .copied()
+        .flatten()
+        .unwrap_or_default();
+
+    Day {
+        maxtemp_c,
+        maxtemp_f: c_to_f(maxtemp_c),
+        mintemp_c,
+        mintemp_f: c_to_f(mintemp_c),
+        avgtemp_c,
+        avgtemp_f: c_to_f(avgtemp_c),
+        maxwind_mph: kph_to_mph(maxwind_kph),
+        maxwind_kph,
+        totalprecip_mm,
+        totalprecip_in: mm_to_in(totalprecip_mm),
+        totalsnow_cm: snow_cm,
+        avgvis_km,
+        avgvis_miles: km_to_miles(avgvis_km),
+        avghumidity: average(|hour| hour.humidity as f64).round() as i64,
+        daily_will_it_rain: i64::from(rain_mm > 0.0),
+        daily_chance_of_rain: if snow_cm > 0.0 && rain_mm == 0.0 {
+            0
+        } else {
+            chance
+        },
+        daily_will_it_snow: i64::from(snow_cm > 0.0),
+        daily_chance_of_snow: if snow_cm > 0.0 { chance } else { 0 },
+        condition: to_condition(code),
+        uv: value(&daily.uv_index_max),
+        air_quality: None,
+    }
+}
+
+fn to_astro(daily: &MeteoDaily, i: usize) -> Astro {
+    let time_of = |values: &[Option<String>]| {
+        values
+            .get(i)
+            .cloned()
+            .flatten()
+            .and_then(|time| time.split('T').nth(1).map(str::to_string))
+            .unwrap_or_default()
+    };
+
+    let (moon_phase, moon_illumination) = daily
+        .time
+        .get(i)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .map(moon_phase)
+        .unwrap_or_default();
+
+    // Open-Meteo has no moonrise and moonset, so those fields stay empty
+    Astro {
+        sunrise: time_of(&daily.sunrise),
+        sunset: time_of(&daily.sunset),
+        moonrise: String::new(),
+        moonset: String::new(),
+        moon_phase,
+        moon_illumination,
+        is_moon_up: 0,
+        is_sun_up: 0,
+    }
+}
+
+/// Calculate the moon phase and its illumination in percent for a day
+///
+/// Uses the mean synodic month counted from the new moon of 2000-01-06,
+/// which is accurate to about a day.
+fn moon_phase(date: NaiveDate) -> (String, f64) {
+    let reference = NaiveDate::from_ymd_opt(2000, 1, 6).expect("valid date");
+    // The new moon was at 18:14 UTC, compare with noon of the day
+    let days = (date - reference).num_days() as f64 - 0.26;
+    let age = days.rem_euclid(SYNODIC_MONTH) / SYNODIC_MONTH;
+    let illumination = (1.0 - (age * std::f64::consts::TAU).cos()) / 2.0 * 100.0;
+    let phase = match (age * 8.0).round() as usize % 8 {
+        0 => "New Moon",
+        1 => "Waxing Crescent",
+        2 => "First Quarter",
+        3 => "Waxing Gibbous",
+        4 => "Full Moon",
+        5 => "Waning Gibbous",
+        6 => "Last Quarter",
+        _ => "Waning Crescent",
+    };
+    (phase.to_string(), illumination.round())
+}
+
+/// Build the air quality from pollutant concentrations and the US AQI
+///
+/// # Arguments
+///
+/// * `pollutants` - CO, NO2, O3, SO2, PM2.5 and PM10 in μg/m³
+/// * `us_aqi` - The US air quality index
+fn to_air_quality(pollutants: [Option<f64>; 6], us_aqi: Option<f64>) -> AirQuality {
+    let [co, no2, o3, so2, pm2_5, pm10] = pollutants.map(Option::unwrap_or_default);
+    AirQuality {
+        co,
+        no2,
+        o3,
+        so2,
+        pm2_5,
+        pm10,
+        us_epa_index: epa_index(us_aqi.unwrap_or_default()),
+        gb_defra_index: defra_index(pm2_5),
+    }
+}
+
+/// Average the hourly air quality of a day, using the worst US AQI
+fn to_daily_air_quality(hourly: &AirQualityHourly, date: &str) -> Option<AirQuality> {
+    let indices: Vec<usize> = (0..hourly.time.len())
+        .filter(|i| hourly.time[*i].starts_with(date))
+        .collect();
+    if indices.is_empty() {
+        return None;
+    }
+
+    let mean = |values: &[Option<f64>]| {
+        let values: Vec<f64> = indices
+            .iter()
+            .filter_map(|i| values.get(*i).copied().flatten())
+            .collect();
+        (!values.is_empty()).then(|| round1(values.iter().sum::<f64>() / values.len() as f64))
+    };
+    let max_aqi = indices
+        .iter()
+        .filter_map(|i| hourly.us_aqi.get(*i).copied().flatten())
+        .reduce(f64::max);
+
+    Some(to_air_quality(
+        [
+            mean(&hourly.carbon_monoxide),
+            mean(&hourly.nitrogen_dioxide),
+            mean(&hourly.ozone),
+            mean(&hourly.sulphur_dioxide),
+            mean(&hourly.pm2_5),
+            mean(&hourly.pm10),
+        ],
+        max_aqi,
+    ))
+}
+
+/// Convert a US AQI value into the US EPA index (1 to 6)
+fn epa_index(aqi: f64) -> i64 {
+    match aqi.round() as i64 {
+        ..=50 => 1,
+        51..=100 => 2,
+        101..=150 => 3,
+        151..=200 => 4,
+        201..=300 => 5,
+        _ => 6,
+    }
+}
+
+/// Convert a PM2.5 concentration in μg/m³ into the UK DEFRA index (1 to 10)
+fn defra_index(pm2_5: f64) -> i64 {
+    const BANDS: [f64; 9] = [11.0, 23.0, 35.0, 41.0, 47.0, 53.0, 58.0, 64.0, 70.0];
+    let pm2_5 = pm2_5.round();
+    BANDS.iter().take_while(|limit| pm2_5 > **limit).count() as i64 + 1
+}
+
+/// Build a condition from a WMO weather interpretation code
+///
+/// The description is looked up in the active language under `wmo.<code>`.
+fn to_condition(code: i64) -> WeatherCondition {
+    WeatherCondition {
+        text: translate(&format!("wmo.{}", code), &[]).unwrap_or_else(|| code.to_string()),
+        icon: String::new(),
+        code,
+    }
+}
+
+/// Convert a local ISO 8601 timestamp (`YYYY-MM-DDTHH:MM`) to a unix timestamp
+fn local_to_epoch(time: &str, utc_offset: i64) -> i64 {
+    NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M")
+        .map(|local| local.and_utc().timestamp() - utc_offset)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::load_embedded_languages;
+
+    const FORECAST: &str = include_str!("../../tests/fixtures/open_meteo/forecast.json");
+    const ARCHIVE: &str = include_str!("../../tests/fixtures/open_meteo/archive.json");
+    const AIR_QUALITY: &str = include_str!("../../tests/fixtures/open_meteo/air_quality.json");
+    const GEOCODING: &str = include_str!("../../tests/fixtures/open_meteo/geocoding.json");
+
+    #[test]
+    fn geocoding_results_become_search_locations() {
+        let data: GeocodingResponse = serde_json::from_str(GEOCODING).unwrap();
+        let locations: Vec<SearchLocation> = data.results.into_iter().map(Into::into).collect();
+
+        assert_eq!(locations.len(), 3);
+        assert_eq!(locations[0].id, 2950159);
+        assert_eq!(locations[0].name, "Berlin");
+        assert_eq!(locations[0].region, "Land Berlin");
+        assert_eq!(locations[0].country, "Germany");
+        assert_eq!(locations[0].lat, 52.52437);
+        assert_eq!(locations[1].region, "New Hampshire");
+        // Small places may lack the region and the country
+        assert_eq!(locations[2].region, "");
+        assert_eq!(locations[2].country, "");
+    }
+
+    #[test]
+    fn empty_geocoding_response_has_no_results() {
+        let data: GeocodingResponse = serde_json::from_str("{}").unwrap();
+        assert!(data.results.is_empty());
+    }
+
+    #[test]
+    fn current_weather_is_mapped_with_converted_units() {
+        let data: MeteoResponse = serde_json::from_str(FORECAST).unwrap();
+        let current = to_weather_current(data.current.as_ref().unwrap(), data.utc_offset_seconds);
+
+        assert_eq!(current.last_updated, "2026-10-18 14:15");
+        let utc = NaiveDateTime::parse_from_str("2026-10-18 12:15", "%Y-%m-%d %H:%M").unwrap();
+        assert_eq!(current.last_updated_epoch, utc.and_utc().timestamp());
+        assert_eq!(current.temp_c, 17.0);
+        assert_eq!(current.temp_f, 62.6);
+        assert_eq!(current.feelslike_c, 15.2);
+        assert_eq!(current.wind_kph, 12.0);
+        assert_eq!(current.wind_mph, 7.5);
+        assert_eq!(current.wind_degree, 202);
+        assert_eq!(current.wind_dir, "SSW");
+        assert_eq!(current.pressure_mb, 1011.0);
+        assert_eq!(current.pressure_in, 29.85);
+        assert_eq!(current.precip_in, 0.0);
+        assert_eq!(current.humidity, 66);
+        assert_eq!(current.cloud, 100);
+        assert_eq!(current.vis_km, 18.0);
+        assert_eq!(current.vis_miles, 11.2);
+        assert_eq!(current.condition.code, 3);
+        assert!(current.air_quality.is_none());
+    }
+
+    #[test]
+    fn forecast_days_group_their_hours() {
+        let data: MeteoResponse = serde_json::from_str(FORECAST).unwrap();
+        let days = to_forecast_days(&data).unwrap();
+
+        assert_eq!(days.len(), 1);
+        let forecast_day = &days[0];
+        assert_eq!(forecast_day.date, "2026-10-18");
+        assert_eq!(forecast_day.hour.len(), 3);
+
+        let day = &forecast_day.day;
+        assert_eq!(day.maxtemp_c, 17.0);
+        assert_eq!(day.mintemp_c, 6.0);
+        assert_eq!(day.avgtemp_c, 16.2);
+        assert_eq!(day.avgvis_km, 18.0);
+        assert_eq!(day.avghumidity, 68);
+        assert_eq!(day.totalprecip_mm, 0.4);
+        assert_eq!(day.daily_will_it_rain, 1);
+        assert_eq!(day.daily_chance_of_rain, 60);
+        assert_eq!(day.daily_chance_of_snow, 0);
+        assert_eq!(day.uv, 2.0);
+        assert_eq!(day.condition.code, 61);
+
+        let astro = &forecast_day.astro;
+        assert_eq!(astro.sunrise, "07:31");
+        assert_eq!(astro.sunset, "18:11");
+        assert_eq!(astro.moonrise, "");
+        assert_eq!(astro.moonset, "");
+
+        let hour = &forecast_day.hour[1];
+        assert_eq!(hour.time, "2026-10-18 13:00");
+        assert_eq!(hour.temp_c, 16.5);
+        assert_eq!(hour.wind_dir, "SW");
+        assert_eq!(hour.chance_of_rain, 60);
+        assert_eq!(hour.will_it_rain, 1);
+        assert_eq!(hour.vis_km, 12.0);
+        // A missing value falls back to zero
+        assert_eq!(forecast_day.hour[2].wind_degree, 0);
+        assert_eq!(forecast_day.hour[2].wind_dir, "N");
+    }
+
+    #[test]
+    fn chance_of_rain_is_only_dropped_for_snow_days() {
+        let mut data: MeteoResponse = serde_json::from_str(FORECAST).unwrap();
+        let daily = data.daily.as_mut().unwrap();
+
+        // Showers may be forecast even though the rain sum rounds to zero
+        daily.rain_sum[0] = Some(0.0);
+        let days = to_forecast_days(&data).unwrap();
+        assert_eq!(days[0].day.daily_will_it_rain, 0);
+        assert_eq!(days[0].day.daily_chance_of_rain, 60);
+
+        let daily = data.daily.as_mut().unwrap();
+        daily.snowfall_sum[0] = Some(1.4);
+        let days = to_forecast_days(&data).unwrap();
+        assert_eq!(days[0].day.daily_chance_of_rain, 0);
+        assert_eq!(days[0].day.daily_chance_of_snow, 60);
+    }
+
+    #[test]
+    fn archive_days_lack_forecast_only_fields() {
+        let data: MeteoResponse = serde_json::from_str(ARCHIVE).unwrap();
+        let days = to_forecast_days(&data).unwrap();
+
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].hour.len(), 3);
+        assert_eq!(days[0].day.maxtemp_c, 17.0);
+        assert_eq!(days[0].day.uv, 0.0);
+        assert_eq!(days[0].day.avgvis_km, 0.0);
+        assert!(days[0].hour.iter().all(|hour| hour.chance_of_rain == 0));
+    }
+
+    #[test]
+    fn response_without_daily_data_is_a_decode_error() {
+        let data: MeteoResponse = serde_json::from_str(
+            r#"{"timezone":"GMT","utc_offset_seconds":0,"hourly":{"time":[],"temperature_2m":[],
+            "relative_humidity_2m":[],"dew_point_2m":[],"apparent_temperature":[],"precipitation":[],
+            "rain":[],"snowfall":[],"weather_code":[],"pressure_msl":[],"cloud_cover":[],
+            "wind_speed_10m":[],"wind_direction_10m":[],"wind_gusts_10m":[],"is_day":[]}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            to_forecast_days(&data),
+            Err(WeatherError::Decode(_))
+        ));
+    }
+
+    #[test]
+    fn air_quality_is_averaged_per_day() {
+        let data: AirQualityResponse = serde_json::from_str(AIR_QUALITY).unwrap();
+        let hourly = data.hourly.as_ref().unwrap();
+
+        let air_quality = to_daily_air_quality(hourly, "2026-10-18").unwrap();
+        assert_eq!(air_quality.co, 180.0);
+        assert_eq!(air_quality.no2, 12.0);
+        // Missing hours are left out of the mean
+        assert_eq!(air_quality.o3, 55.0);
+        assert_eq!(air_quality.pm2_5, 40.0);
+        // The worst hour decides the US EPA index
+        assert_eq!(air_quality.us_epa_index, 3);
+        assert_eq!(air_quality.gb_defra_index, 4);
+        assert!(to_daily_air_quality(hourly, "2026-10-19").is_none());
+
+        let current = data.current.unwrap();
+        let air_quality = to_air_quality(
+            [
+                current.carbon_monoxide,
+                current.nitrogen_dioxide,
+                current.ozone,
+                current.sulphur_dioxide,
+                current.pm2_5,
+                current.pm10,
+            ],
+            current.us_aqi,
+        );
+        assert_eq!(air_quality.pm10, 14.0);
+        assert_eq!(air_quality.us_epa_index, 1);
+        assert_eq!(air_quality.gb_defra_index, 1);
+    }
+
+    #[test]
+    fn air_quality_indices_follow_their_bands() {
+        assert_eq!(epa_index(0.0), 1);
+        assert_eq!(epa_index(50.0), 1);
+        assert_eq!(epa_index(51.0), 2);
+        assert_eq!(epa_index(150.0), 3);
+        assert_eq!(epa_index(200.0), 4);
+        assert_eq!(epa_index(300.0), 5);
+        assert_eq!(epa_index(301.0), 6);
+        assert_eq!(defra_index(0.0), 1);
+        assert_eq!(defra_index(11.0), 1);
+        assert_eq!(defra_index(12.0), 2);
+        assert_eq!(defra_index(70.0), 9);
+        assert_eq!(defra_index(71.0), 10);
+    }
+
+    #[test]
+    fn every_wmo_code_has_a_condition_text() {
+        load_embedded_languages().unwrap();
+        let translations: serde_json::Value =
+            serde_json::from_str(include_str!("../../languages/en.json")).unwrap();
+        let codes = translations["wmo"].as_object().unwrap();
+
+        // All codes of the WMO 4677 subset that Open-Meteo reports
+        let expected = [
+            0, 1, 2, 3, 45, 48, 51, 53, 55, 56, 57, 61, 63, 65, 66, 67, 71, 73, 75, 77, 80, 81, 82,
+            85, 86, 95, 96, 99,
+        ];
+        assert_eq!(codes.len(), expected.len());
+        for code in expected {
+            let condition = to_condition(code);
+            assert_eq!(condition.code, code);
+            assert_eq!(
+                condition.text,
+                codes[&code.to_string()].as_str().unwrap(),
+                "wmo.{}",
+                code
+            );
+        }
+        assert_eq!(to_condition(0).text, "Clear sky");
+        assert_eq!(to_condition(95).text, "Thunderstorm");
+    }
+
+    #[test]
+    fn unknown_wmo_code_shows_the_code() {
+        load_embedded_languages().unwrap();
+        assert_eq!(to_condition(42).text, "42");
+    }
+}
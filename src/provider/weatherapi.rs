@@ -0,0 +1,831 @@
+use std::fmt;
+
+use chrono::NaiveDate;
+use dotenv::{dotenv, var};
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use super::{FetchOptions, ProviderResult, WeatherProvider};
+use crate::client::HttpClient;
+use crate::error::WeatherError;
+use crate::location::LocationQuery;
+use crate::weather::{
+    AirQuality, Alert, Astro, Astronomy, Day, ForecastDay, ForecastWeather, Hour, SearchLocation,
+    Weather, WeatherCondition, WeatherCurrent, WeatherForecast, WeatherLocation, sort_alerts,
+};
+
+const BASE_URL: &str = "https://api.weatherapi.com/v1";
+
+/// Weather provider backed by [weatherapi.com](https://www.weatherapi.com/)
+///
+/// The API key is taken from the configuration if one is set, otherwise it
+/// is read from the `WEATHER_API` environment variable (or a `.env` file) on
+/// every request.
+#[derive(Clone)]
+pub struct WeatherApi {
+    client: HttpClient,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+/// Keeps the API key out of debug output and logs
+impl fmt::Debug for WeatherApi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeatherApi")
+            .field("client", &self.client)
+            .field("base_url", &self.base_url)
+            .field("api_key", &self.api_key.as_ref().map(|_| "REDACTED"))
+            .finish()
+    }
+}
+
+/// The envelope weatherapi.com wraps its error responses in
+#[derive(Debug, Deserialize)]
+struct ApiErrorResponse {
+    error: ApiError,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCurrentResponse {
+    location: ApiLocation,
+    current: ApiCurrent,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiForecastResponse {
+    location: ApiLocation,
+    forecast: ApiForecast,
+    alerts: Option<ApiAlerts>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiAlerts {
+    alert: Vec<ApiAlert>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiAlert {
+    headline: Option<String>,
+    severity: Option<String>,
+    urgency: Option<String>,
+    areas: Option<String>,
+    event: Option<String>,
+    effective: Option<String>,
+    expires: Option<String>,
+    desc: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiAstronomyResponse {
+    location: ApiLocation,
+    astronomy: ApiAstronomy,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiAstronomy {
+    astro: ApiAstro,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiLocation {
+    name: String,
+    region: String,
+    country: String,
+    lat: f64,
+    lon: f64,
+    tz_id: String,
+    localtime_epoch: i64,
+    localtime: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiSearchLocation {
+    id: i64,
+    name: String,
+    region: String,
+    country: String,
+    lat: f64,
+    lon: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCurrent {
+    last_updated_epoch: i64,
+    last_updated: String,
+    temp_c: f64,
+    temp_f: f64,
+    is_day: i64,
+    condition: ApiCondition,
+    wind_mph: f64,
+    wind_kph: f64,
+    wind_degree: i64,
+    wind_dir: String,
+    pressure_mb: f64,
+    pressure_in: f64,
+    precip_mm: f64,
+    precip_in: f64,
+    humidity: i64,
+    cloud: i64,
+    feelslike_c: f64,
+    feelslike_f: f64,
+    vis_km: f64,
+    vis_miles: f64,
+    uv: f64,
+    gust_mph: f64,
+    gust_kph: f64,
+    air_quality: Option<ApiAirQuality>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiAirQuality {
+    co: Option<f64>,
+    no2: Option<f64>,
+    o3: Option<f64>,
+    so2: Option<f64>,
+    pm2_5: Option<f64>,
+    pm10: Option<f64>,
+    #[serde(rename = "us-epa-index")]
+    us_epa_index: Option<i64>,
+    #[serde(rename = "gb-defra-index")]
+    gb_defra_index: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCondition {
+    text: String,
+    icon: String,
+    code: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiForecast {
+    forecastday: Vec<ApiForecastDay>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiForecastDay {
+    date: String,
+    day: ApiDay,
+    astro: ApiAstro,
+    hour: Vec<ApiHour>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiDay {
+    maxtemp_c: f64,
+    maxtemp_f: f64,
+    mintemp_c: f64,
+    mintemp_f: f64,
+    avgtemp_c: f64,
+    avgtemp_f: f64,
+    maxwind_mph: f64,
+    maxwind_kph: f64,
+    totalprecip_mm: f64,
+    totalprecip_in: f64,
+    totalsnow_cm: f64,
+    avgvis_km: f64,
+    avgvis_miles: f64,
+    avghumidity: i64,
+    daily_will_it_rain: i64,
+    daily_chance_of_rain: i64,
+    daily_will_it_snow: i64,
+    daily_chance_of_snow: i64,
+    condition: ApiCondition,
+    uv: f64,
+    air_quality: Option<ApiAirQuality>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiAstro {
+    sunrise: String,
+    sunset: String,
+    moonrise: String,
+    moonset: String,
+    moon_phase: String,
+    moon_illumination: f64,
+    is_moon_up: i64,
+    is_sun_up: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiHour {
+    time: String,
+    temp_c: f64,
+    temp_f: f64,
+    is_day: i64,
+    condition: ApiCondition,
+    wind_mph: f64,
+    wind_kph: f64,
+    wind_degree: i64,
+    wind_dir: String,
+    pressure_mb: f64,
+    pressure_in: f64,
+    precip_mm: f64,
+    precip_in: f64,
+    snow_cm: f64,
+    humidity: i64,
+    cloud: i64,
+    feelslike_c: f64,
+    feelslike_f: f64,
+    windchill_c: f64,
+    windchill_f: f64,
+    heatindex_c: f64,
+    heatindex_f: f64,
+    dewpoint_c: f64,
+    dewpoint_f: f64,
+    will_it_rain: i64,
+    chance_of_rain: i64,
+    will_it_snow: i64,
+    chance_of_snow: i64,
+    vis_km: f64,
+    vis_miles: f64,
+    gust_mph: f64,
+    gust_kph: f64,
+    uv: f64,
+}
+
+impl WeatherApi {
+    /// Create a new weatherapi.com provider
+    ///
+    /// Uses the client's base URL override instead of the public endpoint if
+    /// one is configured.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The HTTP client to send the requests with
+    pub fn new(client: HttpClient) -> Self {
+        let base_url = client.base_url().unwrap_or(BASE_URL).to_string();
+        WeatherApi {
+            client,
+            base_url,
+            api_key: None,
+        }
+    }
+
+    /// Use the given API key instead of the `WEATHER_API` variable
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - The API key, `None` keeps reading the variable
+    pub fn with_api_key(mut self, api_key: Option<String>) -> Self {
+        self.api_key = api_key;
+        self
+    }
+
+    /// Send a request to an endpoint and return the response body
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The endpoint file name, e.g. `current.json`
+    /// * `params` - The query parameters besides the API key
+    ///
+    /// # Errors
+    ///
+    /// If the API key is missing, the request fails, the server answers with
+    /// an error payload or does not answer with a success status
+    async fn fetch(&self, endpoint: &str, params: &[(&str, String)]) -> ProviderResult<String> {
+        let key = match &self.api_key {
+            Some(key) => key.clone(),
+            None => {
+                dotenv().ok();
+                var("WEATHER_API").map_err(|_| WeatherError::MissingApiKey)?
+            }
+        };
+        let mut query = vec![("key", key)];
+        query.extend(params.iter().cloned());
+
+        let resp = self
+            .client
+            .get(&format!("{}/{}", self.base_url, endpoint), &query)
+            .await?;
+        let status = resp.status();
+        let body = resp.text().await?;
+
+        if let Ok(data) = serde_json::from_str::<ApiErrorResponse>(&body) {
+            let query = params
+                .iter()
+                .find(|(name, _)| *name == "q")
+                .map(|(_, value)| value.as_str())
+                .unwrap_or_default();
+            return Err(map_api_error(data.error, query));
+        }
+
+        match status {
+            status if status.is_success() => Ok(body),
+            StatusCode::TOO_MANY_REQUESTS => Err(WeatherError::QuotaExceeded),
+            status => Err(WeatherError::HttpStatus(status.as_u16())),
+        }
+    }
+}
+
+/// Convert a location query into weatherapi.com's `q=` syntax
+fn to_q(query: &LocationQuery) -> String {
+    match query {
+        LocationQuery::City(city) => city.clone(),
+        LocationQuery::Coordinates { lat, lon } => format!("{},{}", lat, lon),
+        LocationQuery::Zip(zip) => zip.clone(),
+        LocationQuery::Iata(iata) => format!("iata:{}", iata),
+        LocationQuery::Metar(metar) => format!("metar:{}", metar),
+        LocationQuery::AutoIp => "auto:ip".to_string(),
+    }
+}
+
+/// Format a flag as weatherapi.com's `yes`/`no` parameter value
+fn yes_no(flag: bool) -> String {
+    if flag { "yes" } else { "no" }.to_string()
+}
+
+/// Map a weatherapi.com error code to a `WeatherError`
+///
+/// See <https://www.weatherapi.com/docs/#intro-error-codes> for the codes.
+///
+/// # Arguments
+///
+/// * `error` - The error returned by the API
+/// * `query` - The location query of the failed request
+fn map_api_error(error: ApiError, query: &str) -> WeatherError {
+    match error.code {
+        1002 => WeatherError::MissingApiKey,
+        1006 => WeatherError::UnknownLocation(query.to_string()),
+        2007 => WeatherError::QuotaExceeded,
+        code => WeatherError::Provider {
+            code,
+            message: error.message,
+        },
+    }
+}
+
+impl WeatherProvider for WeatherApi {
+    async fn current(
+        &self,
+        query: &LocationQuery,
+        lang: &str,
+        options: &FetchOptions,
+    ) -> ProviderResult<Weather> {
+        let body = self
+            .fetch(
+                "current.json",
+                &[
+                    ("q", to_q(query)),
+                    ("aqi", yes_no(options.air_quality)),
+                    ("lang", lang.to_string()),
+                ],
+            )
+            .await?;
+        let data: ApiCurrentResponse = serde_json::from_str(&body)?;
+
+        Ok(data.into())
+    }
+
+    async fn forecast(
+        &self,
+        query: &LocationQuery,
+        days: usize,
+        lang: &str,
+        options: &FetchOptions,
+    ) -> ProviderResult<ForecastWeather> {
+        let body = self
+            .fetch(
+                "forecast.json",
+                &[
+                    ("q", to_q(query)),
+                    ("aqi", yes_no(options.air_quality)),
+                    ("lang", lang.to_string()),
+                    ("days", days.to_string()),
+                    ("alerts", yes_no(options.alerts)),
+                ],
+            )
+            .await?;
+        let data: ApiForecastResponse = serde_json::from_str(&body)?;
+
+        Ok(data.into())
+    }
+
+    async fn history(
+        &self,
+        query: &LocationQuery,
+        date: NaiveDate,
+        lang: &str,
+    ) -> ProviderResult<ForecastWeather> {
+        let body = self
+            .fetch(
+                "history.json",
+                &[
+                    ("q", to_q(query)),
+                    ("lang", lang.to_string()),
+                    ("dt", date.format("%Y-%m-%d").to_string()),
+                ],
+            )
+            .await?;
+        let data: ApiForecastResponse = serde_json::from_str(&body)?;
+
+        Ok(data.into())
+    }
+
+    async fn astronomy(
+        &self,
+        query: &LocationQuery,
+        date: NaiveDate,
+        lang: &str,
+    ) -> ProviderResult<Astronomy> {
+        let date = date.format("%Y-%m-%d").to_string();
+        let body = self
+            .fetch(
+                "astronomy.json",
+                &[
+                    ("q", to_q(query)),
+                    ("lang", lang.to_string()),
+                    ("dt", date.clone()),
+                ],
+            )
+            .await?;
+        let data: ApiAstronomyResponse = serde_json::from_str(&body)?;
+
+        Ok(Astronomy {
+            location: data.location.into(),
+            date,
+            astro: data.astronomy.astro.into(),
+            previous_astro: None,
+            stale_since: None,
+        })
+    }
+
+    async fn search(&self, query: &str, _lang: &str) -> ProviderResult<Vec<SearchLocation>> {
+        let body = self
+            .fetch("search.json", &[("q", query.to_string())])
+            .await?;
+        let data: Vec<ApiSearchLocation> = serde_json::from_str(&body)?;
+
+        Ok(data.into_iter().map(Into::into).collect())
+    }
+}
+
+impl From<ApiCurrentResponse> for Weather {
+    fn from(data: ApiCurrentResponse) -> Self {
+        Weather {
+            location: data.location.into(),
+            current: data.current.into(),
+            stale_since: None,
+        }
+    }
+}
+
+impl From<ApiForecastResponse> for ForecastWeather {
+    fn from(data: ApiForecastResponse) -> Self {
+        let mut alerts: Vec<Alert> = data
+            .alerts
+            .map(|alerts| alerts.alert.into_iter().map(Into::into).collect())
+            .unwrap_or_default();
+        sort_alerts(&mut alerts);
+
+        ForecastWeather {
+            location: data.location.into(),
+            forecast: WeatherForecast {
+                forecastday: data
+                    .forecast
+                    .forecastday
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
+            },
+            alerts,
+            stale_since: None,
+        }
+    }
+}
+
+impl From<ApiAlert> for Alert {
+    fn from(alert: ApiAlert) -> Self {
+        Alert {
+            headline: alert.headline.unwrap_or_default(),
+            event: alert.event.unwrap_or_default(),
+            severity: alert.severity.unwrap_or_default(),
+            urgency: alert.urgency.unwrap_or_default(),
+            areas: alert.areas.unwrap_or_default(),
+            effective: alert.effective.unwrap_or_default(),
+            expires: alert.expires.unwrap_or_default(),
+            description: alert.desc.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<ApiLocation> for WeatherLocation {
+    fn from(location: ApiLocation) -> Self {
+        WeatherLocation {
+            name: location.name,
+            region: location.region,
+            country: location.country,
+            lat: location.lat,
+            lon: location.lon,
+            tz_id: location.tz_id,
+            localtime_epoch: location.localtime_epoch,
+            localtime: location.localtime,
+        }
+    }
+}
+
+impl From<ApiSearchLocation> for SearchLocation {
+    fn from(location: ApiSearchLocation) -> Self {
+        SearchLocation {
+            id: location.id,
+            name: location.name,
+            region: location.region,
+            country: location.country,
+            lat: location.lat,
+            lon: location.lon,
+        }
+    }
+}
+
+impl From<ApiCondition> for WeatherCondition {
+    fn from(condition: ApiCondition) -> Self {
+        WeatherCondition {
+            text: condition.text,
+            icon: condition.icon,
+            code: condition.code,
+        }
+    }
+}
+
+impl From<ApiCurrent> for WeatherCurrent {
+    fn from(current: ApiCurrent) -> Self {
+        WeatherCurrent {
+            last_updated_epoch: current.last_updated_epoch,
+            last_updated: current.last_updated,
+            temp_c: current.temp_c,
+            temp_f: current.temp_f,
+            is_day: current.is_day,
+            condition: current.condition.into(),
+            wind_mph: current.wind_mph,
+            wind_kph: current.wind_kph,
+            wind_degree: current.wind_degree,
+            wind_dir: current.wind_dir,
+            pressure_mb: current.pressure_mb,
+            pressure_in: current.pressure_in,
+            precip_mm: current.precip_mm,
+            precip_in: current.precip_in,
+            humidity: current.humidity,
+            cloud: current.cloud,
+            feelslike_c: current.feelslike_c,
+            feelslike_f: current.feelslike_f,
+            vis_km: current.vis_km,
+            vis_miles: current.vis_miles,
+            uv: current.uv,
+            gust_mph: current.gust_mph,
+            gust_kph: current.gust_kph,
+            air_quality: current.air_quality.map(Into::into),
+        }
+    }
+}
+
+impl From<ApiForecastDay> for ForecastDay {
+    fn from(forecast_day: ApiForecastDay) -> Self {
+        ForecastDay {
+            date: forecast_day.date,
+            day: forecast_day.day.into(),
+            astro: forecast_day.astro.into(),
+            hour: forecast_day.hour.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<ApiDay> for Day {
+    fn from(day: ApiDay) -> Self {
+        Day {
+            maxtemp_c: day.maxtemp_c,
+            maxtemp_f: day.maxtemp_f,
+            mintemp_c: day.mintemp_c,
+            mintemp_f: day.mintemp_f,
+            avgtemp_c: day.avgtemp_c,
+            avgtemp_f: day.avgtemp_f,
+            maxwind_mph: day.maxwind_mph,
+            maxwind_kph: day.maxwind_kph,
+            totalprecip_mm: day.totalprecip_mm,
+            totalprecip_in: day.totalprecip_in,
+            totalsnow_cm: day.totalsnow_cm,
+            avgvis_km: day.avgvis_km,
+            avgvis_miles: day.avgvis_miles,
+            avghumidity: day.avghumidity,
+            daily_will_it_rain: day.daily_will_it_rain,
+            daily_chance_of_rain: day.daily_chance_of_rain,
+            daily_will_it_snow: day.daily_will_it_snow,
+            daily_chance_of_snow: day.daily_chance_of_snow,
+            condition: day.condition.into(),
+            uv: day.uv,
+            air_quality: day.air_quality.map(Into::into),
+        }
+    }
+}
+
+impl From<ApiAirQuality> for AirQuality {
+    fn from(air_quality: ApiAirQuality) -> Self {
+        AirQuality {
+            co: air_quality.co.unwrap_or_default(),
+            no2: air_quality.no2.unwrap_or_default(),
+            o3: air_quality.o3.unwrap_or_default(),
+            so2: air_quality.so2.unwrap_or_default(),
+            pm2_5: air_quality.pm2_5.unwrap_or_default(),
+            pm10: air_quality.pm10.unwrap_or_default(),
+            us_epa_index: air_quality.us_epa_index.unwrap_or_default(),
+            gb_defra_index: air_quality.gb_defra_index.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<ApiAstro> for Astro {
+    fn from(astro: ApiAstro) -> Self {
+        Astro {
+            sunrise: astro_time(astro.sunrise),
+            sunset: astro_time(astro.sunset),
+            moonrise: astro_time(astro.moonrise),
+            moonset: astro_time(astro.moonset),
+            moon_phase: astro.moon_phase,
+            moon_illumination: astro.moon_illumination,
+            is_moon_up: astro.is_moon_up,
+            is_sun_up: astro.is_sun_up,
+        }
+    }
+}
+
+/// Drop the placeholders like `No moonrise` that weatherapi.com sends for
+/// events that do not happen on that day
+fn astro_time(value: String) -> String {
+    if value.starts_with("No ") {
+        String::new()
+    } else {
+        value
+    }
+}
+
+impl From<ApiHour> for Hour {
+    fn from(hour: ApiHour) -> Self {
+        Hour {
+            time: hour.time,
+            temp_c: hour.temp_c,
+            temp_f: hour.temp_f,
+            is_day: hour.is_day,
+            condition: hour.condition.into(),
+            wind_mph: hour.wind_mph,
+            wind_kph: hour.wind_kph,
+            wind_degree: hour.wind_degree,
+            wind_dir: hour.wind_dir,
+            pressure_mb: hour.pressure_mb,
+            pressure_in: hour.pressure_in,
+            precip_mm: hour.precip_mm,
+            precip_in: hour.precip_in,
+            snow_cm: hour.snow_cm,
+            humidity: hour.humidity,
+            cloud: hour.cloud,
+            feelslike_c: hour.feelslike_c,
+            feelslike_f: hour.feelslike_f,
+            windchill_c: hour.windchill_c,
+            windchill_f: hour.windchill_f,
+            heatindex_c: hour.heatindex_c,
+            heatindex_f: hour.heatindex_f,
+            dewpoint_c: hour.dewpoint_c,
+            dewpoint_f: hour.dewpoint_f,
+            will_it_rain: hour.will_it_rain,
+            chance_of_rain: hour.chance_of_rain,
+            will_it_snow: hour.will_it_snow,
+            chance_of_snow: hour.chance_of_snow,
+            vis_km: hour.vis_km,
+            vis_miles: hour.vis_miles,
+            gust_mph: hour.gust_mph,
+            gust_kph: hour.gust_kph,
+            uv: hour.uv,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::HttpConfig;
+
+    fn api_error(body: &str) -> WeatherError {
+        let response: ApiErrorResponse = serde_json::from_str(body).unwrap();
+        map_api_error(response.error, "Atlantis")
+    }
+
+    #[test]
+    fn known_error_codes_become_typed_errors() {
+        assert!(matches!(
+            api_error(r#"{"error":{"code":1002,"message":"API key is invalid or not provided."}}"#),
+            WeatherError::MissingApiKey
+        ));
+        assert!(matches!(
+            api_error(r#"{"error":{"code":1006,"message":"No matching location found."}}"#),
+            WeatherError::UnknownLocation(location) if location == "Atlantis"
+        ));
+        assert!(matches!(
+            api_error(
+                r#"{"error":{"code":2007,"message":"API key has exceeded calls per month quota."}}"#
+            ),
+            WeatherError::QuotaExceeded
+        ));
+    }
+
+    /// Encode the `q` parameter like the HTTP client does
+    fn encoded_q(query: LocationQuery) -> String {
+        reqwest::Client::new()
+            .get(BASE_URL)
+            .query(&[("q", to_q(&query))])
+            .build()
+            .unwrap()
+            .url()
+            .query()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn queries_are_encoded_for_the_q_parameter() {
+        assert_eq!(
+            encoded_q(LocationQuery::City("São Paulo".to_string())),
+            "q=S%C3%A3o+Paulo"
+        );
+        assert_eq!(
+            encoded_q(LocationQuery::City("A & B".to_string())),
+            "q=A+%26+B"
+        );
+        assert_eq!(
+            encoded_q(LocationQuery::Coordinates {
+                lat: -23.55,
+                lon: -46.633
+            }),
+            "q=-23.55%2C-46.633"
+        );
+        assert_eq!(
+            encoded_q(LocationQuery::Zip("SW1A 1AA".to_string())),
+            "q=SW1A+1AA"
+        );
+        assert_eq!(
+            encoded_q(LocationQuery::Iata("FRA".to_string())),
+            "q=iata%3AFRA"
+        );
+        assert_eq!(
+            encoded_q(LocationQuery::Metar("EDDF".to_string())),
+            "q=metar%3AEDDF"
+        );
+        assert_eq!(encoded_q(LocationQuery::AutoIp), "q=auto%3Aip");
+    }
+
+    #[test]
+    fn debug_output_hides_the_api_key() {
+        let api = WeatherApi::new(HttpClient::new(HttpConfig::default()).unwrap())
+            .with_api_key(Some("secret-key-123".to_string()));
+
+        let output = format!("{:?}", api);
+        assert!(!output.contains("secret-key-123"));
+        assert!(output.contains("REDACTED"));
+    }
+
+    #[test]
+    fn missing_astro_events_are_empty() {
+        let astro: ApiAstro = serde_json::from_str(
+            r#"{"sunrise":"07:31 AM","sunset":"06:11 PM","moonrise":"No moonrise",
+            "moonset":"No moonset","moon_phase":"Waning Crescent","moon_illumination":3,
+            "is_moon_up":0,"is_sun_up":1}"#,
+        )
+        .unwrap();
+        let astro = Astro::from(astro);
+
+        assert_eq!(astro.sunrise, "07:31 AM");
+        assert_eq!(astro.sunset, "06:11 PM");
+        assert_eq!(astro.moonrise, "");
+        assert_eq!(astro.moonset, "");
+    }
+
+    #[test]
+    fn other_error_codes_keep_the_provider_message() {
+        for (code, message) in [
+            (1003, "Parameter q is missing."),
+            (2006, "API key provided is invalid"),
+            (2008, "API key has been disabled."),
+            (9999, "Internal application error."),
+        ] {
+            match map_api_error(
+                ApiError {
+                    code,
+                    message: message.to_string(),
+                },
+                "Atlantis",
+            ) {
+                WeatherError::Provider {
+                    code: mapped,
+                    message: text,
+                } => {
+                    assert_eq!(mapped, code);
+                    assert_eq!(text, message);
+                }
+                error => panic!("code {} was mapped to {:?}", code, error),
+            }
+        }
+    }
+}
@@ -0,0 +1,368 @@
+use std::sync::RwLock;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::format::format_number;
+
+/// The units used to print the weather
+static UNITS: RwLock<UnitSettings> = RwLock::new(Units::Metric.settings());
+
+/// The unit system, every quantity can be overridden on its own
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    /// °C, km/h, hPa, mm and km
+    #[default]
+    Metric,
+    /// °F, mph, inHg, inches and miles
+    Imperial,
+    /// °C, mph, hPa, mm and miles
+    Uk,
+    /// °C, m/s, hPa, mm and km
+    Si,
+}
+
+/// The unit of temperatures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum TemperatureUnit {
+    Celsius,
+    Fahrenheit,
+}
+
+/// The unit of wind speeds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum WindUnit {
+    /// Kilometres per hour
+    Kmh,
+    /// Miles per hour
+    Mph,
+    /// Metres per second
+    Ms,
+    Knots,
+    /// The Beaufort scale from 0 to 12
+    Beaufort,
+}
+
+/// The unit of air pressure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum PressureUnit {
+    /// Hectopascal, the same as millibar
+    Hpa,
+    /// Inches of mercury
+    Inhg,
+    /// Millimetres of mercury
+    Mmhg,
+}
+
+/// The unit of precipitation amounts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum PrecipitationUnit {
+    Mm,
+    In,
+}
+
+/// The unit of distances like the visibility
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum DistanceUnit {
+    Km,
+    Miles,
+}
+
+/// The unit of every quantity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnitSettings {
+    pub temperature: TemperatureUnit,
+    pub wind: WindUnit,
+    pub pressure: PressureUnit,
+    pub precipitation: PrecipitationUnit,
+    pub distance: DistanceUnit,
+}
+
+impl Units {
+    /// Get the units of every quantity in this system
+    pub const fn settings(self) -> UnitSettings {
+        match self {
+            Units::Metric => UnitSettings {
+                temperature: TemperatureUnit::Celsius,
+                wind: WindUnit::Kmh,
+                pressure: PressureUnit::Hpa,
+                precipitation: PrecipitationUnit::Mm,
+                distance: DistanceUnit::Km,
+            },
+            Units::Imperial => UnitSettings {
+                temperature: TemperatureUnit::Fahrenheit,
+                wind: WindUnit::Mph,
+                pressure: PressureUnit::Inhg,
+                precipitation: PrecipitationUnit::In,
+                distance: DistanceUnit::Miles,
+            },
+            Units::Uk => UnitSettings {
+                temperature: TemperatureUnit::Celsius,
+                wind: WindUnit::Mph,
+                pressure: PressureUnit::Hpa,
+                precipitation: PrecipitationUnit::Mm,
+                distance: DistanceUnit::Miles,
+            },
+            Units::Si => UnitSettings {
+                temperature: TemperatureUnit::Celsius,
+                wind: WindUnit::Ms,
+                pressure: PressureUnit::Hpa,
+                precipitation: PrecipitationUnit::Mm,
+                distance: DistanceUnit::Km,
+            },
+        }
+    }
+}
+
+impl UnitSettings {
+    /// Format a temperature with its unit
+    ///
+    /// # Arguments
+    ///
+    /// * `celsius` - The temperature in °C
+    /// * `fahrenheit` - The same temperature in °F
+    pub fn temperature(&self, celsius: f64, fahrenheit: f64) -> String {
+        match self.temperature {
+            TemperatureUnit::Celsius => format!("{}°C", format_number(celsius)),
+            TemperatureUnit::Fahrenheit => format!("{}°F", format_number(fahrenheit)),
+        }
+    }
+
+    /// Format a wind speed with its unit
+    ///
+    /// # Arguments
+    ///
+    /// * `kph` - The speed in km/h
+    /// * `mph` - The same speed in mph
+    pub fn wind(&self, kph: f64, mph: f64) -> String {
+        match self.wind {
+            WindUnit::Kmh => format!("{} km/h", format_number(kph)),
+            WindUnit::Mph => format!("{} mph", format_number(mph)),
+            WindUnit::Ms => format!("{} m/s", format_number(kph_to_ms(kph))),
+            WindUnit::Knots => format!("{} kn", format_number(kph_to_knots(kph))),
+            WindUnit::Beaufort => format!("{} Bft", kph_to_beaufort(kph)),
+        }
+    }
+
+    /// Format an air pressure with its unit
+    ///
+    /// # Arguments
+    ///
+    /// * `mb` - The pressure in millibar
+    /// * `inches` - The same pressure in inches of mercury
+    pub fn pressure(&self, mb: f64, inches: f64) -> String {
+        match self.pressure {
+            PressureUnit::Hpa => format!("{} hPa", format_number(mb)),
+            PressureUnit::Inhg => format!("{} inHg", format_number(inches)),
+            PressureUnit::Mmhg => format!("{} mmHg", format_number(mb_to_mmhg(mb))),
+        }
+    }
+
+    /// Format a precipitation amount with its unit
+    ///
+    /// # Arguments
+    ///
+    /// * `mm` - The amount in millimetres
+    /// * `inches` - The same amount in inches
+    pub fn precipitation(&self, mm: f64, inches: f64) -> String {
+        match self.precipitation {
+            PrecipitationUnit::Mm => format!("{} mm", format_number(mm)),
+            PrecipitationUnit::In => format!("{} in", format_number(inches)),
+        }
+    }
+
+    /// Format a distance with its unit
+    ///
+    /// # Arguments
+    ///
+    /// * `km` - The distance in kilometres
+    /// * `miles` - The same distance in miles
+    pub fn distance(&self, km: f64, miles: f64) -> String {
+        match self.distance {
+            DistanceUnit::Km => format!("{} km", format_number(km)),
+            DistanceUnit::Miles => format!("{} mi", format_number(miles)),
+        }
+    }
+}
+
+/// Set the units used to print the weather
+pub fn set_units(settings: UnitSettings) {
+    if let Ok(mut units) = UNITS.write() {
+        *units = settings;
+    }
+}
+
+/// Get the units used to print the weather
+pub fn units() -> UnitSettings {
+    UNITS
+        .read()
+        .map(|units| *units)
+        .unwrap_or(Units::Metric.settings())
+}
+
+/// Round a value to one decimal place
+pub fn round1(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+/// Convert a temperature from °C to °F
+pub fn c_to_f(celsius: f64) -> f64 {
+    round1(celsius * 9.0 / 5.0 + 32.0)
+}
+
+/// Convert a speed from km/h to mph
+pub fn kph_to_mph(kph: f64) -> f64 {
+    round1(kph / 1.609_344)
+}
+
+/// Convert a speed from km/h to m/s
+pub fn kph_to_ms(kph: f64) -> f64 {
+    round1(kph / 3.6)
+}
+
+/// Convert a speed from km/h to knots
+pub fn kph_to_knots(kph: f64) -> f64 {
+    round1(kph / 1.852)
+}
+
+/// Convert a wind speed to the Beaufort scale
+///
+/// Uses the empirical formula `v = 0.836 m/s * B^(3/2)`.
+pub fn kph_to_beaufort(kph: f64) -> u8 {
+    let ms = kph.max(0.0) / 3.6;
+    (ms / 0.836).powf(2.0 / 3.0).round().min(12.0) as u8
+}
+
+/// Convert an amount from millimetres to inches
+pub fn mm_to_in(mm: f64) -> f64 {
+    (mm / 25.4 * 100.0).round() / 100.0
+}
+
+/// Convert a pressure from millibar to inches of mercury
+pub fn mb_to_in(mb: f64) -> f64 {
+    (mb * 0.029_53 * 100.0).round() / 100.0
+}
+
+/// Convert a pressure from millibar to millimetres of mercury
+pub fn mb_to_mmhg(mb: f64) -> f64 {
+    round1(mb * 0.750_062)
+}
+
+/// Convert a distance from kilometres to miles
+pub fn km_to_miles(km: f64) -> f64 {
+    round1(km / 1.609_344)
+}
+
+/// Convert a wind direction in degrees to a 16 point compass direction
+pub fn degrees_to_compass(degrees: f64) -> String {
+    const POINTS: [&str; 16] = [
+        "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW",
+        "NW", "NNW",
+    ];
+    let index = ((degrees.rem_euclid(360.0) / 22.5).round() as usize) % POINTS.len();
+    POINTS[index].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn beaufort_boundaries() {
+        assert_eq!(kph_to_beaufort(0.0), 0);
+        assert_eq!(kph_to_beaufort(-5.0), 0);
+        assert_eq!(kph_to_beaufort(1.0), 0);
+        assert_eq!(kph_to_beaufort(2.0), 1);
+        assert_eq!(kph_to_beaufort(5.0), 1);
+        assert_eq!(kph_to_beaufort(6.0), 2);
+        assert_eq!(kph_to_beaufort(19.0), 3);
+        assert_eq!(kph_to_beaufort(20.0), 4);
+        assert_eq!(kph_to_beaufort(117.0), 11);
+        // Hurricane force and everything above it
+        assert_eq!(kph_to_beaufort(118.0), 12);
+        assert_eq!(kph_to_beaufort(250.0), 12);
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(c_to_f(0.0), 32.0);
+        assert_eq!(c_to_f(-40.0), -40.0);
+        assert_eq!(c_to_f(37.0), 98.6);
+        assert_eq!(kph_to_mph(100.0), 62.1);
+        assert_eq!(kph_to_mph(1.609_344), 1.0);
+        assert_eq!(kph_to_ms(36.0), 10.0);
+        assert_eq!(kph_to_ms(100.0), 27.8);
+        assert_eq!(kph_to_knots(1.852), 1.0);
+        assert_eq!(kph_to_knots(100.0), 54.0);
+        assert_eq!(mm_to_in(25.4), 1.0);
+        assert_eq!(mm_to_in(10.0), 0.39);
+        assert_eq!(mb_to_in(1013.25), 29.92);
+        assert_eq!(mb_to_mmhg(1013.25), 760.0);
+        assert_eq!(km_to_miles(1.609_344), 1.0);
+        assert_eq!(km_to_miles(10.0), 6.2);
+    }
+
+    #[test]
+    fn compass_directions() {
+        assert_eq!(degrees_to_compass(0.0), "N");
+        assert_eq!(degrees_to_compass(11.0), "N");
+        assert_eq!(degrees_to_compass(12.0), "NNE");
+        assert_eq!(degrees_to_compass(180.0), "S");
+        assert_eq!(degrees_to_compass(350.0), "N");
+        assert_eq!(degrees_to_compass(-90.0), "W");
+    }
+
+    #[test]
+    fn unit_system_presets() {
+        let metric = Units::Metric.settings();
+        assert_eq!(metric.temperature, TemperatureUnit::Celsius);
+        assert_eq!(metric.wind, WindUnit::Kmh);
+        assert_eq!(metric.pressure, PressureUnit::Hpa);
+        assert_eq!(metric.precipitation, PrecipitationUnit::Mm);
+        assert_eq!(metric.distance, DistanceUnit::Km);
+
+        let imperial = Units::Imperial.settings();
+        assert_eq!(imperial.temperature, TemperatureUnit::Fahrenheit);
+        assert_eq!(imperial.wind, WindUnit::Mph);
+        assert_eq!(imperial.pressure, PressureUnit::Inhg);
+        assert_eq!(imperial.precipitation, PrecipitationUnit::In);
+        assert_eq!(imperial.distance, DistanceUnit::Miles);
+
+        let uk = Units::Uk.settings();
+        assert_eq!(uk.temperature, TemperatureUnit::Celsius);
+        assert_eq!(uk.wind, WindUnit::Mph);
+        assert_eq!(uk.distance, DistanceUnit::Miles);
+
+        let si = Units::Si.settings();
+        assert_eq!(si.wind, WindUnit::Ms);
+        assert_eq!(si.distance, DistanceUnit::Km);
+
+        assert_eq!(Units::default().settings(), metric);
+    }
+
+    #[test]
+    fn values_are_formatted_with_their_unit() {
+        let settings = UnitSettings {
+            wind: WindUnit::Beaufort,
+            pressure: PressureUnit::Mmhg,
+            ..Units::Si.settings()
+        };
+        assert_eq!(settings.wind(62.0, 38.5), "8 Bft");
+        assert_eq!(settings.pressure(1013.25, 29.92), "760 mmHg");
+        assert_eq!(Units::Si.settings().wind(36.0, 22.4), "10 m/s");
+        assert_eq!(
+            UnitSettings {
+                wind: WindUnit::Knots,
+                ..Units::Si.settings()
+            }
+            .wind(1.852, 1.2),
+            "1 kn"
+        );
+    }
+}
@@ -1,14 +1,796 @@
+use std::collections::BTreeMap;
+use std::fmt;
 use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
-/// Saves the language setting to a configuration file.
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::client::MAX_RETRIES;
+use crate::format::is_valid_date_pattern;
+use crate::location::SavedLocation;
+use crate::output::OutputFormat;
+use crate::provider::ProviderKind;
+use crate::units::{
+    DistanceUnit, PrecipitationUnit, PressureUnit, TemperatureUnit, Units, WindUnit,
+};
+
+const CONFIG_DIR_NAME: &str = "rusty-weather";
+const CONFIG_FILE_NAME: &str = "config.toml";
+const LANGUAGES_DIR_NAME: &str = "languages";
+/// The configuration file of earlier versions
+const LEGACY_CONFIG_FILE: &str = "config.ini";
+/// The translations directory earlier versions needed next to their
+/// configuration file
+const LEGACY_LANGUAGES_DIR: &str = "languages";
+/// The permissions of the configuration file on Unix, readable only by the
+/// user
+#[cfg(unix)]
+const CONFIG_FILE_MODE: u32 = 0o600;
+/// Overrides the path of the configuration file
+const CONFIG_PATH_ENV: &str = "RUSTY_WEATHER_CONFIG";
+/// Prefix of the environment variables that override the configuration
+const ENV_PREFIX: &str = "RUSTY_WEATHER_";
+
+/// Errors that can occur while loading or saving the configuration
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("No configuration directory found, set XDG_CONFIG_HOME or HOME")]
+    NoConfigDir,
+    #[error("Failed to access {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Invalid configuration in {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: Box<toml::de::Error>,
+    },
+    #[error("Failed to write the configuration: {0}")]
+    Serialize(#[from] toml::ser::Error),
+    #[error("Invalid value '{value}' for {key}")]
+    InvalidValue { key: String, value: String },
+    #[error("Unknown setting '{0}'")]
+    UnknownKey(String),
+}
+
+/// The names of all settings, sections are separated with a dot
+pub const CONFIG_KEYS: &[&str] = &[
+    "language",
+    "languages_dir",
+    "units",
+    "temperature_unit",
+    "wind_unit",
+    "pressure_unit",
+    "precipitation_unit",
+    "distance_unit",
+    "default_city",
+    "provider",
+    "api_key",
+    "output",
+    "cache.enabled",
+    "network.base_url",
+    "network.proxy",
+    "network.timeout",
+    "network.retries",
+    "format.decimal_separator",
+    "format.date",
+    "format.day",
+    "format.clock",
+];
+
+/// Whether times are shown on the 12 or 24-hour clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+pub enum ClockFormat {
+    /// `14:30`
+    #[clap(name = "24h")]
+    #[serde(rename = "24h")]
+    H24,
+    /// `02:30 PM`
+    #[clap(name = "12h")]
+    #[serde(rename = "12h")]
+    H12,
+}
+
+/// The settings of the app
+///
+/// Stored as TOML in `$XDG_CONFIG_HOME/rusty-weather/config.toml`. Every
+/// field is optional, missing fields fall back to the defaults. Environment
+/// variables override the file and command line flags override both.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// The language of the app, e.g. `en`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// A directory with translation files that add or override languages
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub languages_dir: Option<PathBuf>,
+    /// The preferred unit system
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub units: Option<Units>,
+    /// The unit of temperatures, overrides the unit system
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature_unit: Option<TemperatureUnit>,
+    /// The unit of wind speeds, overrides the unit system
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wind_unit: Option<WindUnit>,
+    /// The unit of air pressure, overrides the unit system
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pressure_unit: Option<PressureUnit>,
+    /// The unit of precipitation, overrides the unit system
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub precipitation_unit: Option<PrecipitationUnit>,
+    /// The unit of distances, overrides the unit system
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance_unit: Option<DistanceUnit>,
+    /// The city used when no location is given, may be a saved location
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_city: Option<String>,
+    /// The weather provider
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<ProviderKind>,
+    /// The weatherapi.com API key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// The default output format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<OutputFormat>,
+    #[serde(skip_serializing_if = "CacheConfig::is_empty")]
+    pub cache: CacheConfig,
+    #[serde(skip_serializing_if = "NetworkConfig::is_empty")]
+    pub network: NetworkConfig,
+    #[serde(skip_serializing_if = "FormatConfig::is_empty")]
+    pub format: FormatConfig,
+    /// Named locations, usable as `--city @name`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub locations: BTreeMap<String, SavedLocation>,
+}
+
+/// Keeps the API key out of debug output and logs
+impl fmt::Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Config")
+            .field("language", &self.language)
+            .field("languages_dir", &self.languages_dir)
+            .field("units", &self.units)
+            .field("temperature_unit", &self.temperature_unit)
+            .field("wind_unit", &self.wind_unit)
+            .field("pressure_unit", &self.pressure_unit)
+            .field("precipitation_unit", &self.precipitation_unit)
+            .field("distance_unit", &self.distance_unit)
+            .field("default_city", &self.default_city)
+            .field("provider", &self.provider)
+            .field("api_key", &self.api_key.as_ref().map(|_| "REDACTED"))
+            .field("output", &self.output)
+            .field("cache", &self.cache)
+            .field("network", &self.network)
+            .field("format", &self.format)
+            .field("locations", &self.locations)
+            .finish()
+    }
+}
+
+/// The `[cache]` section of the configuration
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// Whether responses are cached, enabled by default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+}
+
+/// The `[network]` section of the configuration
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// Base URL that replaces the provider's endpoint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// Proxy URL for all requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Read timeout in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// How often failed requests are retried
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+}
+
+/// The `[format]` section of the configuration
+///
+/// Overrides the number and date formats of the language.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FormatConfig {
+    /// The decimal separator, e.g. `,`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decimal_separator: Option<char>,
+    /// The `strftime` pattern of dates, e.g. `%d.%m.%Y`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    /// The `strftime` pattern of dates with weekday, e.g. `%A, %d.%m.%Y`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub day: Option<String>,
+    /// Whether times are shown on the 12 or 24-hour clock
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clock: Option<ClockFormat>,
+}
+
+impl CacheConfig {
+    fn is_empty(&self) -> bool {
+        *self == CacheConfig::default()
+    }
+}
+
+impl NetworkConfig {
+    fn is_empty(&self) -> bool {
+        *self == NetworkConfig::default()
+    }
+}
+
+impl FormatConfig {
+    fn is_empty(&self) -> bool {
+        *self == FormatConfig::default()
+    }
+}
+
+impl Config {
+    /// Load the configuration file and apply the environment overrides
+    ///
+    /// A `config.ini` of earlier versions is not read here, call
+    /// [`migrate_legacy_config`] before to carry it over.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be read or parsed, or an environment variable has
+    /// an invalid value
+    pub fn load() -> Result<Self, ConfigError> {
+        let mut config = Config::load_file()?;
+        config.apply_env()?;
+        Ok(config)
+    }
+
+    /// Load only the configuration file, without environment overrides
+    ///
+    /// Use this to change and save the configuration.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be read or parsed
+    pub fn load_file() -> Result<Self, ConfigError> {
+        let path = config_path()?;
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+
+        let content = fs::read_to_string(&path).map_err(|source| ConfigError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        let config: Config = toml::from_str(&content).map_err(|source| ConfigError::Parse {
+            path,
+            source: Box::new(source),
+        })?;
+        if let Some(retries) = config.network.retries {
+            parse_retries("network.retries", &retries.to_string())?;
+        }
+        Ok(config)
+    }
+
+    /// Save the configuration file, creating its directory if needed
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be written
+    pub fn save(&self) -> Result<(), ConfigError> {
+        self.save_to(&config_path()?)
+    }
+
+    fn save_to(&self, path: &Path) -> Result<(), ConfigError> {
+        let content = toml::to_string_pretty(self)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|source| ConfigError::Io {
+                path: dir.to_path_buf(),
+                source,
+            })?;
+        }
+        let io_error = |source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+
+        // The file may hold the API key, so only the user may read it
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+            options.mode(CONFIG_FILE_MODE);
+            if path.exists() {
+                fs::set_permissions(path, fs::Permissions::from_mode(CONFIG_FILE_MODE))
+                    .map_err(io_error)?;
+            }
+        }
+        let mut file = options.open(path).map_err(io_error)?;
+        file.write_all(content.as_bytes()).map_err(io_error)
+    }
+
+    /// Override the settings with `RUSTY_WEATHER_*` environment variables
+    ///
+    /// The API key is also read from `WEATHER_API`, including a `.env` file.
+    ///
+    /// # Errors
+    ///
+    /// If a variable has an invalid value
+    pub fn apply_env(&mut self) -> Result<(), ConfigError> {
+        dotenv::dotenv().ok();
+        let env = |name: &str| {
+            std::env::var(format!("{}{}", ENV_PREFIX, name))
+                .ok()
+                .filter(|value| !value.is_empty())
+        };
+
+        if let Some(language) = env("LANGUAGE") {
+            self.language = Some(language);
+        }
+        if let Some(dir) = env("LANGUAGES_DIR") {
+            self.languages_dir = Some(PathBuf::from(dir));
+        }
+        if let Some(units) = env("UNITS") {
+            self.units = Some(parse_value("units", &units)?);
+        }
+        if let Some(unit) = env("TEMPERATURE_UNIT") {
+            self.temperature_unit = Some(parse_value("temperature_unit", &unit)?);
+        }
+        if let Some(unit) = env("WIND_UNIT") {
+            self.wind_unit = Some(parse_value("wind_unit", &unit)?);
+        }
+        if let Some(unit) = env("PRESSURE_UNIT") {
+            self.pressure_unit = Some(parse_value("pressure_unit", &unit)?);
+        }
+        if let Some(unit) = env("PRECIPITATION_UNIT") {
+            self.precipitation_unit = Some(parse_value("precipitation_unit", &unit)?);
+        }
+        if let Some(unit) = env("DISTANCE_UNIT") {
+            self.distance_unit = Some(parse_value("distance_unit", &unit)?);
+        }
+        if let Some(city) = env("DEFAULT_CITY") {
+            self.default_city = Some(city);
+        }
+        if let Some(provider) = env("PROVIDER") {
+            self.provider = Some(parse_value("provider", &provider)?);
+        }
+        if let Some(key) = env("API_KEY").or_else(|| std::env::var("WEATHER_API").ok()) {
+            self.api_key = Some(key);
+        }
+        if let Some(output) = env("OUTPUT") {
+            self.output = Some(parse_value("output", &output)?);
+        }
+        if let Some(enabled) = env("CACHE") {
+            self.cache.enabled = Some(parse_bool("cache", &enabled)?);
+        }
+        if let Some(base_url) = env("BASE_URL") {
+            self.network.base_url = Some(base_url);
+        }
+        if let Some(proxy) = env("PROXY") {
+            self.network.proxy = Some(proxy);
+        }
+        if let Some(timeout) = env("TIMEOUT") {
+            self.network.timeout = Some(parse_number("timeout", &timeout)?);
+        }
+        if let Some(retries) = env("RETRIES") {
+            self.network.retries = Some(parse_retries("retries", &retries)?);
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Get a setting formatted as on the command line
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the setting, see [`CONFIG_KEYS`]
+    ///
+    /// # Returns
+    ///
+    /// The value or `None` if the setting is not set
+    ///
+    /// # Errors
+    ///
+    /// If there is no setting with the given name
+    pub fn get(&self, key: &str) -> Result<Option<String>, ConfigError> {
+        let value = match key {
+            "language" => self.language.clone(),
+            "languages_dir" => self
+                .languages_dir
+                .as_ref()
+                .map(|dir| dir.display().to_string()),
+            "units" => self.units.as_ref().map(value_name),
+            "temperature_unit" => self.temperature_unit.as_ref().map(value_name),
+            "wind_unit" => self.wind_unit.as_ref().map(value_name),
+            "pressure_unit" => self.pressure_unit.as_ref().map(value_name),
+            "precipitation_unit" => self.precipitation_unit.as_ref().map(value_name),
+            "distance_unit" => self.distance_unit.as_ref().map(value_name),
+            "default_city" => self.default_city.clone(),
+            "provider" => self.provider.as_ref().map(value_name),
+            "api_key" => self.api_key.clone(),
+            "output" => self.output.as_ref().map(value_name),
+            "cache.enabled" => self.cache.enabled.map(|enabled| enabled.to_string()),
+            "network.base_url" => self.network.base_url.clone(),
+            "network.proxy" => self.network.proxy.clone(),
+            "network.timeout" => self.network.timeout.map(|timeout| timeout.to_string()),
+            "network.retries" => self.network.retries.map(|retries| retries.to_string()),
+            "format.decimal_separator" => self
+                .format
+                .decimal_separator
+                .map(|separator| separator.to_string()),
+            "format.date" => self.format.date.clone(),
+            "format.day" => self.format.day.clone(),
+            "format.clock" => self.format.clock.as_ref().map(value_name),
+            _ => return Err(ConfigError::UnknownKey(key.to_string())),
+        };
+        Ok(value)
+    }
+
+    /// Change a setting after validating the value
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the setting, see [`CONFIG_KEYS`]
+    /// * `value` - The new value as it would be given on the command line
+    ///
+    /// # Errors
+    ///
+    /// If there is no setting with the given name or the value is invalid
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), ConfigError> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(invalid_value(key, value));
+        }
+        match key {
+            "language" => self.language = Some(value.to_string()),
+            "languages_dir" => self.languages_dir = Some(PathBuf::from(value)),
+            "units" => self.units = Some(parse_value(key, value)?),
+            "temperature_unit" => self.temperature_unit = Some(parse_value(key, value)?),
+            "wind_unit" => self.wind_unit = Some(parse_value(key, value)?),
+            "pressure_unit" => self.pressure_unit = Some(parse_value(key, value)?),
+            "precipitation_unit" => self.precipitation_unit = Some(parse_value(key, value)?),
+            "distance_unit" => self.distance_unit = Some(parse_value(key, value)?),
+            "default_city" => self.default_city = Some(value.to_string()),
+            "provider" => self.provider = Some(parse_value(key, value)?),
+            "api_key" => self.api_key = Some(value.to_string()),
+            "output" => self.output = Some(parse_value(key, value)?),
+            "cache.enabled" => self.cache.enabled = Some(parse_bool(key, value)?),
+            "network.base_url" => self.network.base_url = Some(parse_url(key, value)?),
+            "network.proxy" => self.network.proxy = Some(parse_url(key, value)?),
+            "network.timeout" => self.network.timeout = Some(parse_number(key, value)?),
+            "network.retries" => self.network.retries = Some(parse_retries(key, value)?),
+            "format.decimal_separator" => {
+                self.format.decimal_separator = Some(parse_char(key, value)?)
+            }
+            "format.date" => self.format.date = Some(parse_date_pattern(key, value)?),
+            "format.day" => self.format.day = Some(parse_date_pattern(key, value)?),
+            "format.clock" => self.format.clock = Some(parse_value(key, value)?),
+            _ => return Err(ConfigError::UnknownKey(key.to_string())),
+        }
+        Ok(())
+    }
+
+    /// Remove a setting, so the default is used again
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the setting, see [`CONFIG_KEYS`]
+    ///
+    /// # Returns
+    ///
+    /// Whether the setting was set before
+    ///
+    /// # Errors
+    ///
+    /// If there is no setting with the given name
+    pub fn unset(&mut self, key: &str) -> Result<bool, ConfigError> {
+        let was_set = self.get(key)?.is_some();
+        match key {
+            "language" => self.language = None,
+            "languages_dir" => self.languages_dir = None,
+            "units" => self.units = None,
+            "temperature_unit" => self.temperature_unit = None,
+            "wind_unit" => self.wind_unit = None,
+            "pressure_unit" => self.pressure_unit = None,
+            "precipitation_unit" => self.precipitation_unit = None,
+            "distance_unit" => self.distance_unit = None,
+            "default_city" => self.default_city = None,
+            "provider" => self.provider = None,
+            "api_key" => self.api_key = None,
+            "output" => self.output = None,
+            "cache.enabled" => self.cache.enabled = None,
+            "network.base_url" => self.network.base_url = None,
+            "network.proxy" => self.network.proxy = None,
+            "network.timeout" => self.network.timeout = None,
+            "network.retries" => self.network.retries = None,
+            "format.decimal_separator" => self.format.decimal_separator = None,
+            "format.date" => self.format.date = None,
+            "format.day" => self.format.day = None,
+            "format.clock" => self.format.clock = None,
+            _ => return Err(ConfigError::UnknownKey(key.to_string())),
+        }
+        Ok(was_set)
+    }
+
+    /// Get the directory with the user's translation files
+    ///
+    /// Defaults to `languages` next to the configuration file.
+    ///
+    /// # Returns
+    ///
+    /// The directory or `None` if no configuration directory can be
+    /// determined
+    pub fn languages_dir(&self) -> Option<PathBuf> {
+        self.languages_dir.clone().or_else(|| {
+            let path = config_path().ok()?;
+            Some(path.parent()?.join(LANGUAGES_DIR_NAME))
+        })
+    }
+}
+
+/// Get the accepted values of a setting with a fixed set of values
 ///
 /// # Arguments
-/// * `language` - The language to save.
+///
+/// * `key` - The name of the setting
+///
+/// # Returns
+///
+/// The values or `None` if the setting accepts free text or numbers
+pub fn possible_values(key: &str) -> Option<Vec<String>> {
+    fn names<T: ValueEnum>() -> Vec<String> {
+        T::value_variants().iter().map(value_name).collect()
+    }
+
+    match key {
+        "units" => Some(names::<Units>()),
+        "temperature_unit" => Some(names::<TemperatureUnit>()),
+        "wind_unit" => Some(names::<WindUnit>()),
+        "pressure_unit" => Some(names::<PressureUnit>()),
+        "precipitation_unit" => Some(names::<PrecipitationUnit>()),
+        "distance_unit" => Some(names::<DistanceUnit>()),
+        "provider" => Some(names::<ProviderKind>()),
+        "output" => Some(names::<OutputFormat>()),
+        "cache.enabled" => Some(vec!["true".to_string(), "false".to_string()]),
+        "format.clock" => Some(names::<ClockFormat>()),
+        _ => None,
+    }
+}
+
+/// Get the path of the configuration file
+///
+/// Uses `$RUSTY_WEATHER_CONFIG` if set, otherwise `config.toml` in
+/// `$XDG_CONFIG_HOME/rusty-weather`, `~/.config/rusty-weather` or
+/// `%APPDATA%\rusty-weather` on Windows.
+///
+/// # Errors
+///
+/// If none of the directories can be determined
+pub fn config_path() -> Result<PathBuf, ConfigError> {
+    if let Some(path) = std::env::var_os(CONFIG_PATH_ENV).filter(|path| !path.is_empty()) {
+        return Ok(PathBuf::from(path));
+    }
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+        .ok_or(ConfigError::NoConfigDir)?;
+    Ok(base.join(CONFIG_DIR_NAME).join(CONFIG_FILE_NAME))
+}
+
+/// Convert the `config.ini` of earlier versions into the configuration file
+///
+/// Earlier versions only ran from their project directory, which held the
+/// `languages` directory, and stored nothing but `language=<code>` there.
+/// The old file is only migrated if it is found next to the binary or in the
+/// project directory of a binary built with cargo, sits next to a
+/// `languages` directory and contains exactly that line. It is left in place.
+/// Nothing happens if the configuration file already exists.
+///
+/// # Returns
+///
+/// The path of the migrated file or `None` if nothing was migrated
 ///
 /// # Errors
-/// Returns an error if the file cannot be written.
-pub fn save_language(language: &str) -> Result<(), std::io::Error> {
-    let contents = format!("language={}", language);
-    fs::write("config.ini", contents)?;
-    Ok(())
+///
+/// If the new configuration file cannot be written
+pub fn migrate_legacy_config() -> Result<Option<PathBuf>, ConfigError> {
+    let path = config_path()?;
+    if path.exists() {
+        return Ok(None);
+    }
+
+    for dir in legacy_config_dirs() {
+        if !dir.join(LEGACY_LANGUAGES_DIR).is_dir() {
+            continue;
+        }
+        let legacy = dir.join(LEGACY_CONFIG_FILE);
+        let Some(language) = fs::read_to_string(&legacy)
+            .ok()
+            .and_then(|content| parse_legacy_config(&content))
+        else {
+            continue;
+        };
+        let config = Config {
+            language: Some(language),
+            ..Default::default()
+        };
+        config.save_to(&path)?;
+        return Ok(Some(legacy));
+    }
+    Ok(None)
+}
+
+/// Get the directories earlier versions were run from: the directory of the
+/// binary and, for binaries in `target/<profile>`, the project directory
+fn legacy_config_dirs() -> Vec<PathBuf> {
+    let Some(exe_dir) = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+    else {
+        return Vec::new();
+    };
+    let project_dir = exe_dir
+        .parent()
+        .filter(|dir| dir.ends_with("target"))
+        .and_then(Path::parent)
+        .map(Path::to_path_buf);
+    std::iter::once(exe_dir).chain(project_dir).collect()
+}
+
+/// Get the language of an old `config.ini`
+///
+/// # Returns
+///
+/// The language or `None` if the file has anything but a single
+/// `language=<code>` line
+fn parse_legacy_config(content: &str) -> Option<String> {
+    let mut lines = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+    let (key, value) = lines.next()?.split_once('=')?;
+    let value = value.trim();
+    let is_code = !value.is_empty()
+        && value.len() <= 16
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    (key.trim() == "language" && is_code && lines.next().is_none()).then(|| value.to_string())
+}
+
+/// Get the command line name of an enum value
+fn value_name<T: ValueEnum>(value: &T) -> String {
+    value
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+/// Parse the value of an enum setting by its command line name
+fn parse_value<T: ValueEnum>(key: &str, value: &str) -> Result<T, ConfigError> {
+    T::from_str(value, true).map_err(|_| invalid_value(key, value))
+}
+
+fn parse_number<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, ConfigError> {
+    value.parse().map_err(|_| invalid_value(key, value))
+}
+
+fn parse_retries(key: &str, value: &str) -> Result<u32, ConfigError> {
+    parse_number(key, value)
+        .ok()
+        .filter(|retries| *retries <= MAX_RETRIES)
+        .ok_or_else(|| invalid_value(key, value))
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool, ConfigError> {
+    match value.to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err(invalid_value(key, value)),
+    }
+}
+
+/// Check that a value is an absolute HTTP(S) URL
+fn parse_url(key: &str, value: &str) -> Result<String, ConfigError> {
+    match reqwest::Url::parse(value) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(value.to_string()),
+        _ => Err(invalid_value(key, value)),
+    }
+}
+
+fn parse_char(key: &str, value: &str) -> Result<char, ConfigError> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(char), None) => Ok(char),
+        _ => Err(invalid_value(key, value)),
+    }
+}
+
+/// Check that a value is a valid `strftime` pattern
+fn parse_date_pattern(key: &str, value: &str) -> Result<String, ConfigError> {
+    if is_valid_date_pattern(value) {
+        Ok(value.to_string())
+    } else {
+        Err(invalid_value(key, value))
+    }
+}
+
+fn invalid_value(key: &str, value: &str) -> ConfigError {
+    ConfigError::InvalidValue {
+        key: key.to_string(),
+        value: value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_config_with_only_a_language_is_migrated() {
+        assert_eq!(parse_legacy_config("language=de\n"), Some("de".to_string()));
+        assert_eq!(
+            parse_legacy_config("\n language = en_US \n"),
+            Some("en_US".to_string())
+        );
+    }
+
+    #[test]
+    fn debug_output_hides_the_api_key() {
+        let config = Config {
+            api_key: Some("secret-key-123".to_string()),
+            default_city: Some("Berlin".to_string()),
+            ..Default::default()
+        };
+
+        let output = format!("{:?}", config);
+        assert!(!output.contains("secret-key-123"));
+        assert!(output.contains("REDACTED"));
+        assert!(output.contains("Berlin"));
+    }
+
+    #[test]
+    fn unrelated_ini_files_are_not_migrated() {
+        assert_eq!(parse_legacy_config("[database]\nhost=localhost\n"), None);
+        assert_eq!(parse_legacy_config("language=de\nhost=localhost\n"), None);
+        assert_eq!(parse_legacy_config("language=\n"), None);
+        assert_eq!(parse_legacy_config("language=../../etc\n"), None);
+        assert_eq!(parse_legacy_config(""), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn saved_file_is_only_readable_by_the_user() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("rusty-weather-test-{}", std::process::id()));
+        let path = dir.join(CONFIG_FILE_NAME);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, "").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        let config = Config {
+            api_key: Some("secret".to_string()),
+            ..Default::default()
+        };
+        config.save_to(&path).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+        fs::remove_dir_all(&dir).ok();
+        assert_eq!(mode, 0o600);
+    }
 }
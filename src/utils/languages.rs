@@ -0,0 +1,444 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use lingua_i18n_rs::prelude::{Lingua, LinguaError};
+use serde_json::Value;
+use thiserror::Error;
+
+/// The language used when no available language is configured or detected
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// The environment variables that select the locale, by priority
+const LOCALE_VARIABLES: &[&str] = &["LC_ALL", "LC_MESSAGES", "LANG"];
+
+/// The translations compiled into the binary
+const EMBEDDED_LANGUAGES: &[(&str, &str)] = &[
+    ("en", include_str!("../../languages/en.json")),
+    ("de", include_str!("../../languages/de.json")),
+];
+
+/// The translations of all loaded languages, kept for the fallback and the
+/// completeness check
+static LANGUAGES: RwLock<BTreeMap<String, Value>> = RwLock::new(BTreeMap::new());
+
+/// Errors that can occur while loading a translation file
+#[derive(Debug, Error)]
+pub enum LanguageError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Invalid translation file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error("Failed to load {path}: {source}")]
+    Load { path: PathBuf, source: LinguaError },
+}
+
+/// Load the translations embedded in the binary
+///
+/// # Errors
+///
+/// If an embedded translation is not valid JSON
+pub fn load_embedded_languages() -> Result<(), LinguaError> {
+    for (lang, json) in EMBEDDED_LANGUAGES {
+        register(lang, json)?;
+    }
+    Ok(())
+}
+
+/// Translate a key into the active language
+///
+/// Falls back to English if the active language lacks the key.
+///
+/// # Arguments
+///
+/// * `key` - The key of the text, e.g. `current.title`
+/// * `params` - The values of the `{{name}}` placeholders
+///
+/// # Returns
+///
+/// The translated text or `None` if no language has the key
+pub fn translate(key: &str, params: &[(&str, &str)]) -> Option<String> {
+    if let Ok(text) = Lingua::t(key, params) {
+        return Some(text);
+    }
+    let languages = LANGUAGES.read().ok()?;
+    let mut text = lookup(languages.get(DEFAULT_LANGUAGE)?, key)?.to_string();
+    for (name, value) in params {
+        text = text.replace(&format!("{{{{{}}}}}", name), value);
+    }
+    Some(text)
+}
+
+/// Translate a key into the active language, never failing
+///
+/// Like [`translate`], but returns the key itself if no language has it.
+pub fn t(key: &str, params: &[(&str, &str)]) -> String {
+    translate(key, params).unwrap_or_else(|| key.to_string())
+}
+
+/// Find the loaded language matching a language code or locale
+///
+/// Codes are compared case-insensitively with `-` and `_` treated alike. If
+/// there is no exact match the region is dropped, so `de_AT.UTF-8` selects
+/// `de`.
+///
+/// # Arguments
+///
+/// * `code` - A language code like `de` or a locale like `de_AT.UTF-8`
+///
+/// # Returns
+///
+/// The code of the loaded language or `None` if none matches
+pub fn match_language(code: &str) -> Option<String> {
+    // Drop the encoding and modifier, e.g. `.UTF-8` and `@euro`
+    let locale = code.trim().split(['.', '@']).next()?;
+    if locale.is_empty() || locale == "C" || locale == "POSIX" {
+        return None;
+    }
+
+    let normalize = |code: &str| code.replace('-', "_").to_lowercase();
+    let wanted = normalize(locale);
+    let languages = Lingua::get_languages().ok()?;
+    let find = |wanted: &str| {
+        languages
+            .iter()
+            .find(|lang| normalize(lang) == wanted)
+            .cloned()
+    };
+    find(&wanted).or_else(|| find(wanted.split('_').next()?))
+}
+
+/// Detect the language from the locale of the environment
+///
+/// Uses the first set variable of `LC_ALL`, `LC_MESSAGES` and `LANG`, like
+/// the C library does.
+///
+/// # Returns
+///
+/// The matching loaded language or `None` if the locale is not set or no
+/// language matches
+pub fn detect_language() -> Option<String> {
+    detect_language_with(|name| std::env::var(name).ok())
+}
+
+/// Detect the language from locale variables read through `var`
+fn detect_language_with(var: impl Fn(&str) -> Option<String>) -> Option<String> {
+    let locale = LOCALE_VARIABLES
+        .iter()
+        .filter_map(|name| var(name))
+        .find(|value| !value.is_empty())?;
+    match_language(&locale)
+}
+
+/// Load the translation files of a directory on top of the embedded ones
+///
+/// Every `<lang>.json` in the directory adds a language. Files of embedded
+/// languages only have to contain the strings they change, all other
+/// strings are kept. A missing directory is not an error.
+///
+/// # Arguments
+///
+/// * `dir` - The directory with the translation files
+///
+/// # Returns
+///
+/// The errors of the files that could not be loaded, the other files are
+/// loaded anyway
+pub fn load_language_dir(dir: &Path) -> Vec<LanguageError> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .filter_map(|path| load_language_file(&path).err())
+        .collect()
+}
+
+fn load_language_file(path: &Path) -> Result<(), LanguageError> {
+    let Some(lang) = path.file_stem().and_then(|stem| stem.to_str()) else {
+        return Ok(());
+    };
+    let content = fs::read_to_string(path).map_err(|source| LanguageError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let parse_error = |source| LanguageError::Parse {
+        path: path.to_path_buf(),
+        source,
+    };
+    let overrides: Value = serde_json::from_str(&content).map_err(parse_error)?;
+
+    let embedded = LANGUAGES
+        .read()
+        .ok()
+        .and_then(|languages| languages.get(lang).cloned());
+    let translations = match embedded {
+        Some(mut translations) => {
+            merge(&mut translations, overrides);
+            translations
+        }
+        None => overrides,
+    };
+    register(lang, &translations.to_string()).map_err(|source| LanguageError::Load {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Load the translations of a language into Lingua and keep a copy
+fn register(lang: &str, json: &str) -> Result<(), LinguaError> {
+    Lingua::load_translations_from_str(lang, json)?;
+    if let (Ok(translations), Ok(mut languages)) = (serde_json::from_str(json), LANGUAGES.write()) {
+        languages.insert(lang.to_string(), translations);
+    }
+    Ok(())
+}
+
+/// Find the text of a dotted key in a translation tree
+fn lookup<'a>(translations: &'a Value, key: &str) -> Option<&'a str> {
+    key.split('.')
+        .try_fold(translations, |value, part| value.get(part))?
+        .as_str()
+}
+
+/// Merge nested JSON objects, values of `overrides` win
+fn merge(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        (Value::Object(base), Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides,
+    }
+}
+
+/// The differences of a language to the English reference
+#[derive(Debug, Default)]
+pub struct LanguageReport {
+    pub language: String,
+    /// Keys of the reference the language lacks
+    pub missing: Vec<String>,
+    /// Keys of the language the reference does not have
+    pub extra: Vec<String>,
+    /// Texts that use other placeholders than the reference
+    pub placeholder_mismatches: Vec<PlaceholderMismatch>,
+}
+
+/// A text that uses other placeholders than the reference
+#[derive(Debug)]
+pub struct PlaceholderMismatch {
+    pub key: String,
+    /// The placeholders of the reference text
+    pub expected: Vec<String>,
+    /// The placeholders of the checked text
+    pub found: Vec<String>,
+}
+
+impl LanguageReport {
+    /// Get the number of problems found
+    pub fn problem_count(&self) -> usize {
+        self.missing.len() + self.extra.len() + self.placeholder_mismatches.len()
+    }
+}
+
+/// Compare all loaded languages with the English reference
+///
+/// # Returns
+///
+/// A report for every language except English, sorted by language code
+pub fn check_languages() -> Vec<LanguageReport> {
+    let Ok(languages) = LANGUAGES.read() else {
+        return Vec::new();
+    };
+    let reference = languages
+        .get(DEFAULT_LANGUAGE)
+        .map(flatten)
+        .unwrap_or_default();
+
+    languages
+        .iter()
+        .filter(|(lang, _)| lang.as_str() != DEFAULT_LANGUAGE)
+        .map(|(lang, translations)| {
+            let texts = flatten(translations);
+            let mut report = LanguageReport {
+                language: lang.clone(),
+                ..Default::default()
+            };
+            for (key, expected) in &reference {
+                match texts.get(key) {
+                    None => report.missing.push(key.clone()),
+                    Some(text) if placeholders(text) != placeholders(expected) => {
+                        report.placeholder_mismatches.push(PlaceholderMismatch {
+                            key: key.clone(),
+                            expected: placeholders(expected).into_iter().collect(),
+                            found: placeholders(text).into_iter().collect(),
+                        })
+                    }
+                    Some(_) => {}
+                }
+            }
+            report.extra = texts
+                .keys()
+                .filter(|key| !reference.contains_key(*key))
+                .cloned()
+                .collect();
+            report
+        })
+        .collect()
+}
+
+/// Collect the texts of a translation tree by their dotted keys
+fn flatten(translations: &Value) -> BTreeMap<String, String> {
+    fn walk(value: &Value, prefix: &str, texts: &mut BTreeMap<String, String>) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    let key = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    walk(value, &key, texts);
+                }
+            }
+            Value::String(text) => {
+                texts.insert(prefix.to_string(), text.clone());
+            }
+            other => {
+                texts.insert(prefix.to_string(), other.to_string());
+            }
+        }
+    }
+
+    let mut texts = BTreeMap::new();
+    walk(translations, "", &mut texts);
+    texts
+}
+
+/// Get the names of the `{{name}}` placeholders of a text
+fn placeholders(text: &str) -> BTreeSet<String> {
+    text.split("{{")
+        .skip(1)
+        .filter_map(|part| part.split_once("}}"))
+        .map(|(name, _)| name.trim().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn detect(variables: &[(&str, &str)]) -> Option<String> {
+        load_embedded_languages().unwrap();
+        detect_language_with(|name| {
+            variables
+                .iter()
+                .find(|(variable, _)| *variable == name)
+                .map(|(_, value)| value.to_string())
+        })
+    }
+
+    #[test]
+    fn locales_select_their_language() {
+        load_embedded_languages().unwrap();
+        assert_eq!(match_language("de"), Some("de".to_string()));
+        assert_eq!(match_language("DE"), Some("de".to_string()));
+        assert_eq!(match_language("de_AT.UTF-8"), Some("de".to_string()));
+        assert_eq!(match_language("de-CH"), Some("de".to_string()));
+        assert_eq!(match_language("de_DE@euro"), Some("de".to_string()));
+        assert_eq!(match_language("en_GB.UTF-8"), Some("en".to_string()));
+    }
+
+    #[test]
+    fn unknown_locales_select_no_language() {
+        load_embedded_languages().unwrap();
+        assert_eq!(match_language("C"), None);
+        assert_eq!(match_language("C.UTF-8"), None);
+        assert_eq!(match_language("POSIX"), None);
+        assert_eq!(match_language(""), None);
+        assert_eq!(match_language("fr_FR.UTF-8"), None);
+    }
+
+    #[test]
+    fn language_is_detected_from_the_environment() {
+        assert_eq!(detect(&[("LANG", "de_AT.UTF-8")]), Some("de".to_string()));
+        // LC_ALL wins over LANG, empty variables are skipped
+        assert_eq!(
+            detect(&[("LC_ALL", "en_US.UTF-8"), ("LANG", "de_DE.UTF-8")]),
+            Some("en".to_string())
+        );
+        assert_eq!(
+            detect(&[("LC_ALL", ""), ("LANG", "de_DE.UTF-8")]),
+            Some("de".to_string())
+        );
+    }
+
+    #[test]
+    fn unusable_locales_fall_back_to_english() {
+        let language = |variables: &[(&str, &str)]| {
+            detect(variables).unwrap_or_else(|| DEFAULT_LANGUAGE.to_string())
+        };
+        assert_eq!(language(&[("LANG", "C")]), "en");
+        assert_eq!(language(&[("LANG", "POSIX")]), "en");
+        assert_eq!(language(&[("LANG", "fr_FR.UTF-8")]), "en");
+        assert_eq!(language(&[]), "en");
+    }
+
+    #[test]
+    fn overrides_are_merged_into_the_translations() {
+        let mut translations = json!({
+            "current": {"title": "Current weather", "temperature": "Temperature"},
+            "search": {"title": "Locations"}
+        });
+        merge(
+            &mut translations,
+            json!({"current": {"title": "Weather now"}, "alerts": {"title": "Alerts"}}),
+        );
+
+        assert_eq!(lookup(&translations, "current.title"), Some("Weather now"));
+        assert_eq!(
+            lookup(&translations, "current.temperature"),
+            Some("Temperature")
+        );
+        assert_eq!(lookup(&translations, "search.title"), Some("Locations"));
+        assert_eq!(lookup(&translations, "alerts.title"), Some("Alerts"));
+    }
+
+    #[test]
+    fn placeholders_are_collected_by_name() {
+        assert_eq!(
+            placeholders("{{index}}. {{ location }} ({{index}})"),
+            BTreeSet::from(["index".to_string(), "location".to_string()])
+        );
+        assert!(placeholders("No placeholders {here}").is_empty());
+    }
+
+    #[test]
+    fn translations_are_flattened_to_dotted_keys() {
+        let texts = flatten(&json!({"a": {"b": "text", "c": {"d": "deep"}}, "e": 1}));
+        assert_eq!(texts["a.b"], "text");
+        assert_eq!(texts["a.c.d"], "deep");
+        assert_eq!(texts["e"], "1");
+    }
+}
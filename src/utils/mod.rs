@@ -1,3 +1,11 @@
 mod config;
+mod languages;
 
-pub use config::save_language;
+pub use config::{
+    CONFIG_KEYS, CacheConfig, ClockFormat, Config, ConfigError, FormatConfig, NetworkConfig,
+    config_path, migrate_legacy_config, possible_values,
+};
+pub use languages::{
+    DEFAULT_LANGUAGE, LanguageError, LanguageReport, PlaceholderMismatch, check_languages,
+    detect_language, load_embedded_languages, load_language_dir, match_language, t, translate,
+};
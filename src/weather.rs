@@ -1,22 +1,45 @@
-use dotenv::{dotenv, var};
-use lingua_i18n_rs::prelude::Lingua;
+use std::io::IsTerminal;
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::format::{
+    clock_format, format_date_str, format_datetime_str, format_day_str, format_number, format_time,
+    format_time_str,
+};
+use crate::units::units;
+use crate::utils::{ClockFormat, t, translate};
+
 const PRINT_WIDTH: usize = 70;
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Column widths of the hourly table: time, temperature, feels like, rain
+/// chance, wind and gusts. The condition takes the remaining space.
+const HOURLY_COLUMNS: [usize; 6] = [6, 8, 8, 6, 15, 11];
+
+/// Width of the time column of the hourly table on the 12-hour clock
+const HOURLY_TIME_COLUMN_12H: usize = 9;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Weather {
     pub location: WeatherLocation,
     pub current: WeatherCurrent,
+    /// When the data was fetched, if it is served from an expired cache entry
+    #[serde(skip)]
+    pub stale_since: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ForecastWeather {
     pub location: WeatherLocation,
     pub forecast: WeatherForecast,
+    #[serde(default)]
+    pub alerts: Vec<Alert>,
+    /// When the data was fetched, if it is served from an expired cache entry
+    #[serde(skip)]
+    pub stale_since: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WeatherLocation {
     pub name: String,
     pub region: String,
@@ -28,7 +51,17 @@ pub struct WeatherLocation {
     pub localtime: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchLocation {
+    pub id: i64,
+    pub name: String,
+    pub region: String,
+    pub country: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WeatherCurrent {
     pub last_updated_epoch: i64,
     pub last_updated: String,
@@ -53,21 +86,23 @@ pub struct WeatherCurrent {
     pub uv: f64,
     pub gust_mph: f64,
     pub gust_kph: f64,
+    #[serde(default)]
+    pub air_quality: Option<AirQuality>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WeatherCondition {
     pub text: String,
     pub icon: String,
     pub code: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WeatherForecast {
     pub forecastday: Vec<ForecastDay>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ForecastDay {
     pub date: String,
     pub day: Day,
@@ -75,7 +110,7 @@ pub struct ForecastDay {
     pub hour: Vec<Hour>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Day {
     pub maxtemp_c: f64,
     pub maxtemp_f: f64,
@@ -97,9 +132,40 @@ pub struct Day {
     pub daily_chance_of_snow: i64,
     pub condition: WeatherCondition,
     pub uv: f64,
+    #[serde(default)]
+    pub air_quality: Option<AirQuality>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Pollutant concentrations in μg/m³ and the derived air quality indices
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AirQuality {
+    pub co: f64,
+    pub no2: f64,
+    pub o3: f64,
+    pub so2: f64,
+    pub pm2_5: f64,
+    pub pm10: f64,
+    /// US EPA index from 1 (good) to 6 (hazardous)
+    pub us_epa_index: i64,
+    /// UK DEFRA index from 1 (low) to 10 (very high)
+    pub gb_defra_index: i64,
+}
+
+/// An official weather warning issued for the location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alert {
+    pub headline: String,
+    pub event: String,
+    /// `Extreme`, `Severe`, `Moderate`, `Minor` or `Unknown`
+    pub severity: String,
+    pub urgency: String,
+    pub areas: String,
+    pub effective: String,
+    pub expires: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Astro {
     pub sunrise: String,
     pub sunset: String,
@@ -111,7 +177,21 @@ pub struct Astro {
     pub is_sun_up: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Sun and moon data of a single day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Astronomy {
+    pub location: WeatherLocation,
+    pub date: String,
+    pub astro: Astro,
+    /// The astro data of the day before, to compare the day lengths
+    #[serde(default)]
+    pub previous_astro: Option<Astro>,
+    /// When the data was fetched, if it is served from an expired cache entry
+    #[serde(skip)]
+    pub stale_since: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Hour {
     pub time: String,
     pub temp_c: f64,
@@ -201,48 +281,19 @@ impl Weather {
                 uv: 0.0,
                 gust_mph: 0.0,
                 gust_kph: 0.0,
+                air_quality: None,
             },
+            stale_since: None,
         }
     }
 
-    /// Fetch the current weather for a given city
-    ///
-    /// # Arguments
-    ///
-    /// * `city` - A string slice that holds the name of the city
-    ///
-    /// # Returns
-    ///
-    /// A Result object with the Weather struct or an error
-    ///
-    /// # Errors
-    ///
-    /// If the request fails or the response cannot be deserialized
-    pub async fn get_current_weather(
-        &self,
-        city: String,
-        lang: String,
-    ) -> Result<Self, Box<dyn std::error::Error>> {
-        dotenv().ok();
-        let url = &format!(
-            "http://api.weatherapi.com/v1/current.json?key={}&q={}&aqi=no&lang={}",
-            var("WEATHER_API").unwrap(),
-            city,
-            lang
-        );
-
-        let resp = reqwest::get(url).await;
-        let data = serde_json::from_str(&resp?.text().await?)?;
-
-        Ok(data)
-    }
-
     /// Print the current weather information
     ///
     /// This method prints the current weather information in a formatted way
     /// to the console
     pub fn print_current_weather(&self) {
         let width = PRINT_WIDTH;
+        let units = units();
 
         print_separator(width, 't');
         print_line(
@@ -251,10 +302,14 @@ impl Weather {
             width,
             LineType::Header,
         );
+        print_stale_marker(self.stale_since.as_deref(), width);
         print_separator(width, 'm');
         print_line(
             "current.date",
-            &[("date", self.location.localtime.as_str())],
+            &[(
+                "date",
+                format_datetime_str(&self.location.localtime).as_str(),
+            )],
             width,
             LineType::Text,
         );
@@ -278,39 +333,34 @@ impl Weather {
         );
         print_line(
             "current.temperature",
-            &[
-                ("temperature_c", self.current.temp_c.to_string().as_str()),
-                ("temperature_f", self.current.temp_f.to_string().as_str()),
-            ],
+            &[(
+                "temperature",
+                units
+                    .temperature(self.current.temp_c, self.current.temp_f)
+                    .as_str(),
+            )],
             width,
             LineType::Text,
         );
         print_line(
             "current.feels_like",
-            &[
-                (
-                    "feels_like_c",
-                    self.current.feelslike_c.to_string().as_str(),
-                ),
-                (
-                    "feels_like_f",
-                    self.current.feelslike_f.to_string().as_str(),
-                ),
-            ],
+            &[(
+                "feels_like",
+                units
+                    .temperature(self.current.feelslike_c, self.current.feelslike_f)
+                    .as_str(),
+            )],
             width,
             LineType::Text,
         );
-        // TODO: Create a better way to handle the daytime
+        let daytime = if self.current.is_day == 1 {
+            t("current.daytimes.day", &[])
+        } else {
+            t("current.daytimes.night", &[])
+        };
         print_line(
             "current.daytime",
-            &[(
-                "daytime",
-                if self.current.is_day == 1 {
-                    "day"
-                } else {
-                    "night"
-                },
-            )],
+            &[("daytime", daytime.as_str())],
             width,
             LineType::Text,
         );
@@ -333,50 +383,83 @@ impl Weather {
         print_line(
             "current.wind",
             &[
-                ("wind_speed", self.current.wind_kph.to_string().as_str()),
-                ("wind_direction", self.current.wind_dir.as_str()),
+                (
+                    "wind_speed",
+                    units
+                        .wind(self.current.wind_kph, self.current.wind_mph)
+                        .as_str(),
+                ),
+                (
+                    "wind_direction",
+                    wind_direction_name(&self.current.wind_dir).as_str(),
+                ),
             ],
             width,
             LineType::Text,
         );
         print_line(
             "current.gusts",
-            &[
-                ("wind_gusts", self.current.gust_kph.to_string().as_str()),
-                ("gusts_mph", self.current.gust_mph.to_string().as_str()),
-            ],
+            &[(
+                "wind_gusts",
+                units
+                    .wind(self.current.gust_kph, self.current.gust_mph)
+                    .as_str(),
+            )],
             width,
             LineType::Text,
         );
         print_line(
             "current.precipitation",
-            &[("precipitation", self.current.precip_mm.to_string().as_str())],
+            &[(
+                "precipitation",
+                units
+                    .precipitation(self.current.precip_mm, self.current.precip_in)
+                    .as_str(),
+            )],
             width,
             LineType::Text,
         );
         print_line(
             "current.pressure",
-            &[("pressure", self.current.pressure_mb.to_string().as_str())],
+            &[(
+                "pressure",
+                units
+                    .pressure(self.current.pressure_mb, self.current.pressure_in)
+                    .as_str(),
+            )],
             width,
             LineType::Text,
         );
         print_line(
             "current.visibility",
-            &[("visibility", self.current.vis_km.to_string().as_str())],
+            &[(
+                "visibility",
+                units
+                    .distance(self.current.vis_km, self.current.vis_miles)
+                    .as_str(),
+            )],
             width,
             LineType::Text,
         );
         print_line(
             "current.uv_index",
-            &[("uv_index", self.current.uv.to_string().as_str())],
+            &[("uv_index", format_number(self.current.uv).as_str())],
             width,
             LineType::Text,
         );
 
+        if let Some(air_quality) = &self.current.air_quality {
+            print_separator(width, 'm');
+            print_air_quality(air_quality, width);
+        }
+
         print_separator(width, 'm');
         print_line(
             "current.last_updated",
-            &[("last_updated", self.current.last_updated.as_str())],
+            &[(
+                "last_updated",
+                format_datetime_str(&self.current.last_updated).as_str(),
+            )],
             width,
             LineType::Text,
         );
@@ -406,45 +489,82 @@ impl ForecastWeather {
             forecast: WeatherForecast {
                 forecastday: Vec::new(),
             },
+            alerts: Vec::new(),
+            stale_since: None,
         }
     }
 
-    pub async fn get_forecast_weather(
-        &self,
-        days: usize,
-        city: String,
-        lang: String,
-    ) -> Result<Self, Box<dyn std::error::Error>> {
-        dotenv().ok();
-        let url = &format!(
-            "http://api.weatherapi.com/v1/forecast.json?key={}&q={}&aqi=no&lang={}&days={}&alerts=no",
-            var("WEATHER_API").unwrap(),
-            city,
-            lang,
-            days
+    /// Print the weather alerts for the location
+    ///
+    /// This method prints every alert with its severity, urgency, validity
+    /// and description, or a note if there are no active alerts
+    pub fn print_alerts(&self) {
+        let width = PRINT_WIDTH;
+        print_separator(width, 't');
+        print_line(
+            "alerts.title",
+            &[("city", self.location.name.as_str())],
+            width,
+            LineType::Header,
         );
+        print_stale_marker(self.stale_since.as_deref(), width);
+        print_separator(width, 'm');
+        if self.alerts.is_empty() {
+            print_line("alerts.none", &[], width, LineType::Text);
+        }
+        for (i, alert) in self.alerts.iter().enumerate() {
+            if i > 0 {
+                print_separator(width, 'm');
+            }
+            print_alert(alert, width, true);
+        }
 
-        let resp = reqwest::get(url).await;
-        let data = serde_json::from_str(&resp?.text().await?)?;
-
-        Ok(data)
+        print_separator(width, 'b');
     }
 
     pub fn print_forecast_weather(&self) {
+        self.print_days("forecast.forecast", "forecast.day_forecast");
+    }
+
+    /// Print the observed weather of past days
+    ///
+    /// This method uses the same layout as the forecast with its own title
+    pub fn print_history_weather(&self) {
+        self.print_days("history.history", "history.day");
+    }
+
+    /// Print the days with the translated titles
+    ///
+    /// # Arguments
+    ///
+    /// * `title_key` - The translation key of the title
+    /// * `day_key` - The translation key of the title of every day
+    fn print_days(&self, title_key: &str, day_key: &str) {
         let width = PRINT_WIDTH;
+        let units = units();
         print_separator(width, 't');
         print_line(
-            "forecast.forecast",
+            title_key,
             &[("city", self.location.name.as_str())],
             width,
             LineType::Header,
         );
+        print_stale_marker(self.stale_since.as_deref(), width);
+
+        if !self.alerts.is_empty() {
+            print_separator(width, 'm');
+            print_line("alerts.section", &[], width, LineType::Header);
+            for alert in &self.alerts {
+                print_separator(width, 'm');
+                print_alert(alert, width, false);
+            }
+        }
 
         for forecast_day in &self.forecast.forecastday {
             print_separator(width, 'm');
             print_line(
-                "forecast.day_forecast",
-                &[("date", forecast_day.date.as_str())],
+                day_key,
+                &[("date", format_day_str(&forecast_day.date).as_str())],
                 width,
                 LineType::Text,
             );
@@ -452,20 +572,16 @@ impl ForecastWeather {
                 "forecast.temperature_range",
                 &[
                     (
-                        "min_temp_c",
-                        forecast_day.day.mintemp_c.to_string().as_str(),
+                        "min_temp",
+                        units
+                            .temperature(forecast_day.day.mintemp_c, forecast_day.day.mintemp_f)
+                            .as_str(),
                     ),
                     (
-                        "min_temp_f",
-                        forecast_day.day.mintemp_f.to_string().as_str(),
-                    ),
-                    (
-                        "max_temp_c",
-                        forecast_day.day.maxtemp_c.to_string().as_str(),
-                    ),
-                    (
-                        "max_temp_f",
-                        forecast_day.day.maxtemp_f.to_string().as_str(),
+                        "max_temp",
+                        units
+                            .temperature(forecast_day.day.maxtemp_c, forecast_day.day.maxtemp_f)
+                            .as_str(),
                     ),
                 ],
                 width,
@@ -473,16 +589,12 @@ impl ForecastWeather {
             );
             print_line(
                 "forecast.avg_temperature",
-                &[
-                    (
-                        "avg_temp_c",
-                        forecast_day.day.avgtemp_c.to_string().as_str(),
-                    ),
-                    (
-                        "avg_temp_f",
-                        forecast_day.day.avgtemp_f.to_string().as_str(),
-                    ),
-                ],
+                &[(
+                    "avg_temp",
+                    units
+                        .temperature(forecast_day.day.avgtemp_c, forecast_day.day.avgtemp_f)
+                        .as_str(),
+                )],
                 width,
                 LineType::Text,
             );
@@ -496,12 +608,17 @@ impl ForecastWeather {
                 "forecast.precipitation",
                 &[(
                     "precipitation",
-                    forecast_day.day.totalprecip_mm.to_string().as_str(),
+                    units
+                        .precipitation(
+                            forecast_day.day.totalprecip_mm,
+                            forecast_day.day.totalprecip_in,
+                        )
+                        .as_str(),
                 )],
                 width,
                 LineType::Text,
             );
-            if forecast_day.day.daily_will_it_rain > 0 {
+            if forecast_day.day.daily_chance_of_rain > 0 {
                 print_line(
                     "forecast.chance_of_rain",
                     &[(
@@ -512,7 +629,7 @@ impl ForecastWeather {
                     LineType::Text,
                 );
             }
-            if forecast_day.day.daily_will_it_snow > 0 {
+            if forecast_day.day.daily_chance_of_snow > 0 {
                 print_line(
                     "forecast.chance_of_snow",
                     &[(
@@ -525,16 +642,12 @@ impl ForecastWeather {
             }
             print_line(
                 "forecast.wind",
-                &[
-                    (
-                        "wind_kph",
-                        forecast_day.day.maxwind_kph.to_string().as_str(),
-                    ),
-                    (
-                        "wind_mph",
-                        forecast_day.day.maxwind_mph.to_string().as_str(),
-                    ),
-                ],
+                &[(
+                    "wind",
+                    units
+                        .wind(forecast_day.day.maxwind_kph, forecast_day.day.maxwind_mph)
+                        .as_str(),
+                )],
                 width,
                 LineType::Text,
             );
@@ -549,19 +662,338 @@ impl ForecastWeather {
             );
             print_line(
                 "forecast.uv_index",
-                &[("uv", forecast_day.day.uv.to_string().as_str())],
+                &[("uv", format_number(forecast_day.day.uv).as_str())],
                 width,
                 LineType::Text,
             );
             print_line(
                 "forecast.sunrise",
-                &[("sunrise", forecast_day.astro.sunrise.as_str())],
+                &[("sunrise", astro_time(&forecast_day.astro.sunrise).as_str())],
                 width,
                 LineType::Text,
             );
             print_line(
                 "forecast.sunset",
-                &[("sunset", forecast_day.astro.sunset.as_str())],
+                &[("sunset", astro_time(&forecast_day.astro.sunset).as_str())],
+                width,
+                LineType::Text,
+            );
+            if let Some(air_quality) = &forecast_day.day.air_quality {
+                print_line(
+                    "forecast.air_quality",
+                    &[
+                        ("index", air_quality.us_epa_index.to_string().as_str()),
+                        ("category", epa_category(air_quality.us_epa_index).as_str()),
+                    ],
+                    width,
+                    LineType::Text,
+                );
+            }
+        }
+
+        print_separator(width, 'b');
+    }
+
+    /// Only keep the hours within a time window of every day
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The first hour to keep, inclusive
+    /// * `to` - The last hour to keep, inclusive
+    ///
+    /// If `from` is later than `to` the window wraps past midnight, e.g.
+    /// `22:00` to `02:00` keeps the night hours.
+    pub fn filter_hours(&mut self, from: Option<NaiveTime>, to: Option<NaiveTime>) {
+        for forecast_day in &mut self.forecast.forecastday {
+            forecast_day.hour.retain(|hour| {
+                let Some(time) = hour
+                    .time
+                    .split(' ')
+                    .nth(1)
+                    .and_then(|time| NaiveTime::parse_from_str(time, "%H:%M").ok())
+                else {
+                    return true;
+                };
+                is_in_window(time, from, to)
+            });
+        }
+    }
+
+    /// Print the hourly forecast information
+    ///
+    /// This method prints one compact table row per hour, grouped by day
+    pub fn print_hourly_forecast(&self) {
+        self.print_hours("forecast.hourly_forecast", "forecast.day_forecast");
+    }
+
+    /// Print the observed weather of past days hour by hour
+    pub fn print_hourly_history(&self) {
+        self.print_hours("history.hourly_history", "history.day");
+    }
+
+    /// Print one table row per hour with the translated titles
+    ///
+    /// # Arguments
+    ///
+    /// * `title_key` - The translation key of the title
+    /// * `day_key` - The translation key of the title of every day
+    fn print_hours(&self, title_key: &str, day_key: &str) {
+        let width = PRINT_WIDTH;
+        let units = units();
+        print_separator(width, 't');
+        print_line(
+            title_key,
+            &[("city", self.location.name.as_str())],
+            width,
+            LineType::Header,
+        );
+        print_stale_marker(self.stale_since.as_deref(), width);
+
+        for forecast_day in &self.forecast.forecastday {
+            print_separator(width, 'm');
+            print_line(
+                day_key,
+                &[("date", format_day_str(&forecast_day.date).as_str())],
+                width,
+                LineType::Text,
+            );
+            print_separator(width, 'm');
+            let header = [
+                "time",
+                "temperature",
+                "feels_like",
+                "rain",
+                "wind",
+                "gusts",
+                "condition",
+            ]
+            .map(|column| t(&format!("forecast.hourly.{}", column), &[]));
+            print_text(&table_row(&header), width);
+
+            for hour in &forecast_day.hour {
+                let row = [
+                    format_time_str(hour.time.split(' ').nth(1).unwrap_or(&hour.time)),
+                    units.temperature(hour.temp_c, hour.temp_f),
+                    units.temperature(hour.feelslike_c, hour.feelslike_f),
+                    format!("{}%", hour.chance_of_rain),
+                    format!(
+                        "{} {}",
+                        units.wind(hour.wind_kph, hour.wind_mph),
+                        wind_direction_name(&hour.wind_dir)
+                    ),
+                    units.wind(hour.gust_kph, hour.gust_mph),
+                    hour.condition.text.clone(),
+                ];
+                print_text(&table_row(&row), width);
+            }
+        }
+
+        print_separator(width, 'b');
+    }
+}
+
+impl WeatherLocation {
+    /// Get the date at the location at a given moment
+    ///
+    /// The UTC offset of the location is taken from its local time when the
+    /// data was fetched, so it also works with cached data.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The moment to get the date for
+    ///
+    /// # Returns
+    ///
+    /// The date or `None` if the local time is missing or invalid
+    pub fn date_at(&self, now: DateTime<Utc>) -> Option<NaiveDate> {
+        let local = NaiveDateTime::parse_from_str(self.localtime.trim(), "%Y-%m-%d %H:%M").ok()?;
+        let utc = DateTime::from_timestamp(self.localtime_epoch, 0)?.naive_utc();
+        // The local time has no seconds, offsets are whole quarter hours
+        let quarters = ((local - utc).num_seconds() as f64 / 900.0).round() as i64;
+        Some((now.naive_utc() + Duration::minutes(quarters * 15)).date())
+    }
+}
+
+impl SearchLocation {
+    /// Get the name, region and country joined for display
+    ///
+    /// Empty parts are left out, small places often have no region.
+    pub fn display_name(&self) -> String {
+        [&self.name, &self.region, &self.country]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl Alert {
+    /// Get the rank of the alert's severity, the most severe alert first
+    pub fn severity_rank(&self) -> u8 {
+        match self.severity.to_lowercase().as_str() {
+            "extreme" => 0,
+            "severe" => 1,
+            "moderate" => 2,
+            "minor" => 3,
+            _ => 4,
+        }
+    }
+}
+
+impl Astro {
+    /// Get the time between sunrise and sunset
+    ///
+    /// # Returns
+    ///
+    /// The day length or `None` if the sun does not rise or set that day
+    pub fn day_length(&self) -> Option<Duration> {
+        let sunrise = parse_clock_time(&self.sunrise)?;
+        let sunset = parse_clock_time(&self.sunset)?;
+        (sunset > sunrise).then(|| sunset - sunrise)
+    }
+
+    /// Get the time halfway between sunrise and sunset
+    ///
+    /// # Returns
+    ///
+    /// The solar noon or `None` if the sun does not rise or set that day
+    pub fn solar_noon(&self) -> Option<NaiveTime> {
+        let sunrise = parse_clock_time(&self.sunrise)?;
+        Some(sunrise + self.day_length()? / 2)
+    }
+}
+
+impl Astronomy {
+    /// Get how much longer the day is than the day before
+    ///
+    /// # Returns
+    ///
+    /// The change, negative if the day is shorter, or `None` if one of the
+    /// day lengths is unknown
+    pub fn day_length_change(&self) -> Option<Duration> {
+        let previous = self.previous_astro.as_ref()?.day_length()?;
+        Some(self.astro.day_length()? - previous)
+    }
+
+    /// Print the sun and moon information
+    ///
+    /// This method prints all astro fields together with the day length, the
+    /// solar noon and the change of the day length
+    pub fn print_astronomy(&self) {
+        let width = PRINT_WIDTH;
+        let astro = &self.astro;
+        let yes_no = |flag: i64| {
+            t(
+                if flag == 1 {
+                    "astronomy.yes"
+                } else {
+                    "astronomy.no"
+                },
+                &[],
+            )
+        };
+
+        print_separator(width, 't');
+        print_line(
+            "astronomy.title",
+            &[
+                ("city", self.location.name.as_str()),
+                ("date", format_date_str(&self.date).as_str()),
+            ],
+            width,
+            LineType::Header,
+        );
+        print_stale_marker(self.stale_since.as_deref(), width);
+        print_separator(width, 'm');
+        print_line("astronomy.sun", &[], width, LineType::Header);
+        print_line(
+            "forecast.sunrise",
+            &[("sunrise", astro_time(&astro.sunrise).as_str())],
+            width,
+            LineType::Text,
+        );
+        print_line(
+            "forecast.sunset",
+            &[("sunset", astro_time(&astro.sunset).as_str())],
+            width,
+            LineType::Text,
+        );
+        if let Some(solar_noon) = astro.solar_noon() {
+            print_line(
+                "astronomy.solar_noon",
+                &[("time", format_time(solar_noon).as_str())],
+                width,
+                LineType::Text,
+            );
+        }
+        if let Some(day_length) = astro.day_length() {
+            print_line(
+                "astronomy.day_length",
+                &[
+                    ("hours", day_length.num_hours().to_string().as_str()),
+                    (
+                        "minutes",
+                        (day_length.num_minutes() % 60).to_string().as_str(),
+                    ),
+                ],
+                width,
+                LineType::Text,
+            );
+        }
+        if let Some(change) = self.day_length_change() {
+            let seconds = change.num_seconds();
+            print_line(
+                "astronomy.day_length_change",
+                &[
+                    ("sign", if seconds < 0 { "-" } else { "+" }),
+                    ("minutes", (seconds.abs() / 60).to_string().as_str()),
+                    ("seconds", (seconds.abs() % 60).to_string().as_str()),
+                ],
+                width,
+                LineType::Text,
+            );
+        }
+        print_line(
+            "astronomy.is_sun_up",
+            &[("value", yes_no(astro.is_sun_up).as_str())],
+            width,
+            LineType::Text,
+        );
+        print_separator(width, 'm');
+        print_line("astronomy.moon", &[], width, LineType::Header);
+        print_line(
+            "astronomy.moonrise",
+            &[("moonrise", astro_time(&astro.moonrise).as_str())],
+            width,
+            LineType::Text,
+        );
+        print_line(
+            "astronomy.moonset",
+            &[("moonset", astro_time(&astro.moonset).as_str())],
+            width,
+            LineType::Text,
+        );
+        print_line(
+            "astronomy.moon_phase",
+            &[("phase", moon_phase_name(&astro.moon_phase).as_str())],
+            width,
+            LineType::Text,
+        );
+        print_line(
+            "astronomy.moon_illumination",
+            &[(
+                "illumination",
+                format_number(astro.moon_illumination).as_str(),
+            )],
+            width,
+            LineType::Text,
+        );
+        // Without moonrise and moonset the provider does not know it either
+        if !astro.moonrise.is_empty() || !astro.moonset.is_empty() {
+            print_line(
+                "astronomy.is_moon_up",
+                &[("value", yes_no(astro.is_moon_up).as_str())],
                 width,
                 LineType::Text,
             );
@@ -571,6 +1003,344 @@ impl ForecastWeather {
     }
 }
 
+/// Print a numbered list of locations
+///
+/// # Arguments
+///
+/// * `query` - The search query the locations were found for
+/// * `locations` - The locations to print
+pub fn print_locations(query: &str, locations: &[SearchLocation]) {
+    let width = PRINT_WIDTH;
+    print_separator(width, 't');
+    print_line("search.title", &[("query", query)], width, LineType::Header);
+    print_separator(width, 'm');
+    for (i, location) in locations.iter().enumerate() {
+        print_line(
+            "search.location",
+            &[
+                ("index", (i + 1).to_string().as_str()),
+                ("location", location.display_name().as_str()),
+            ],
+            width,
+            LineType::Text,
+        );
+        print_line(
+            "search.details",
+            &[
+                ("lat", format_number(location.lat).as_str()),
+                ("lon", format_number(location.lon).as_str()),
+                ("id", location.id.to_string().as_str()),
+            ],
+            width,
+            LineType::Text,
+        );
+    }
+    print_separator(width, 'b');
+}
+
+/// Check whether a time of day lies within a window, both ends inclusive
+///
+/// A window whose start is later than its end wraps past midnight.
+fn is_in_window(time: NaiveTime, from: Option<NaiveTime>, to: Option<NaiveTime>) -> bool {
+    match (from, to) {
+        (Some(from), Some(to)) if from > to => time >= from || time <= to,
+        _ => from.is_none_or(|from| time >= from) && to.is_none_or(|to| time <= to),
+    }
+}
+
+/// Parse a time of day like `07:31 AM` or `07:31`
+///
+/// # Returns
+///
+/// The time or `None` for values like `No sunrise`
+pub fn parse_clock_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%I:%M %p")
+        .or_else(|_| NaiveTime::parse_from_str(value.trim(), "%H:%M"))
+        .ok()
+}
+
+/// Format the time of a sun or moon event
+///
+/// # Arguments
+///
+/// * `value` - The time, empty if the event does not happen that day
+fn astro_time(value: &str) -> String {
+    if value.is_empty() {
+        t("astronomy.none", &[])
+    } else {
+        format_time_str(value)
+    }
+}
+
+/// Get the translated name of a moon phase
+///
+/// The phases are stored with their English names like `Waxing Crescent`,
+/// unknown names are returned as they are.
+///
+/// # Arguments
+///
+/// * `phase` - The English name of the moon phase
+pub fn moon_phase_name(phase: &str) -> String {
+    if phase.is_empty() {
+        return "–".to_string();
+    }
+    let key = phase.trim().to_lowercase().replace(' ', "_");
+    translate(&format!("astronomy.moon_phases.{}", key), &[]).unwrap_or_else(|| phase.to_string())
+}
+
+/// Get the translated abbreviation of a compass point
+///
+/// Unknown abbreviations are returned as they are.
+///
+/// # Arguments
+///
+/// * `direction` - One of the 16 English compass points, e.g. `NNE`
+pub fn wind_direction_name(direction: &str) -> String {
+    let key = direction.trim().to_uppercase();
+    translate(&format!("wind_directions.{}", key), &[]).unwrap_or_else(|| direction.to_string())
+}
+
+/// Sort alerts by severity, the most severe alert first
+///
+/// Alerts of the same severity are sorted by the time they take effect,
+/// alerts without a valid time come last and keep their order.
+///
+/// # Arguments
+///
+/// * `alerts` - The alerts to sort in place
+pub fn sort_alerts(alerts: &mut [Alert]) {
+    alerts.sort_by_cached_key(|alert| {
+        let effective = DateTime::parse_from_rfc3339(alert.effective.trim()).ok();
+        (alert.severity_rank(), effective.is_none(), effective)
+    });
+}
+
+/// Helper function to print a single alert
+///
+/// # Arguments
+///
+/// * `alert` - The alert to print
+/// * `width` - A width to calculate the padding
+/// * `details` - Whether to include the areas and the description
+fn print_alert(alert: &Alert, width: usize, details: bool) {
+    for line in wrap_text(&alert.headline, width - 2) {
+        print_text(&line, width);
+    }
+    print_line(
+        "alerts.event",
+        &[
+            ("event", alert.event.as_str()),
+            ("severity", severity_label(&alert.severity).as_str()),
+            ("urgency", alert.urgency.as_str()),
+        ],
+        width,
+        LineType::Text,
+    );
+    print_line(
+        "alerts.validity",
+        &[
+            ("effective", format_datetime_str(&alert.effective).as_str()),
+            ("expires", format_datetime_str(&alert.expires).as_str()),
+        ],
+        width,
+        LineType::Text,
+    );
+    if !details {
+        return;
+    }
+    if !alert.areas.is_empty() {
+        print_line(
+            "alerts.areas",
+            &[("areas", alert.areas.as_str())],
+            width,
+            LineType::Text,
+        );
+    }
+    for line in wrap_text(&alert.description, width - 2) {
+        print_text(&line, width);
+    }
+}
+
+/// Helper function to get the colour coded label of an alert severity
+fn severity_label(severity: &str) -> String {
+    let color = match severity.to_lowercase().as_str() {
+        "extreme" => "35",
+        "severe" => "31",
+        "moderate" => "33",
+        "minor" => "32",
+        _ => "0",
+    };
+    colorize(severity, color)
+}
+
+/// Helper function to wrap a text into lines of at most `width` characters
+///
+/// Words longer than the width are not split.
+fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+    }
+    lines
+}
+
+/// Helper function to print a warning if the data is outdated
+///
+/// # Arguments
+///
+/// * `stale_since` - When the data was fetched, if it is outdated
+/// * `width` - A width to calculate the padding
+fn print_stale_marker(stale_since: Option<&str>, width: usize) {
+    if let Some(time) = stale_since {
+        let text = t(
+            "cache.stale",
+            &[("time", format_datetime_str(time).as_str())],
+        );
+        for line in wrap_text(&text, width - 2) {
+            print_text(&colorize(&line, "33"), width);
+        }
+    }
+}
+
+/// Helper function to print the air quality section
+///
+/// # Arguments
+///
+/// * `air_quality` - The air quality to print
+/// * `width` - A width to calculate the padding
+fn print_air_quality(air_quality: &AirQuality, width: usize) {
+    print_line("air_quality.title", &[], width, LineType::Header);
+    print_separator(width, 'm');
+    for (key, value) in [
+        ("air_quality.co", air_quality.co),
+        ("air_quality.no2", air_quality.no2),
+        ("air_quality.o3", air_quality.o3),
+        ("air_quality.so2", air_quality.so2),
+        ("air_quality.pm2_5", air_quality.pm2_5),
+        ("air_quality.pm10", air_quality.pm10),
+    ] {
+        print_line(
+            key,
+            &[("value", format_number(value).as_str())],
+            width,
+            LineType::Text,
+        );
+    }
+    print_line(
+        "air_quality.us_epa_index",
+        &[
+            ("index", air_quality.us_epa_index.to_string().as_str()),
+            ("category", epa_category(air_quality.us_epa_index).as_str()),
+        ],
+        width,
+        LineType::Text,
+    );
+    print_line(
+        "air_quality.gb_defra_index",
+        &[
+            ("index", air_quality.gb_defra_index.to_string().as_str()),
+            (
+                "category",
+                defra_category(air_quality.gb_defra_index).as_str(),
+            ),
+        ],
+        width,
+        LineType::Text,
+    );
+}
+
+/// Helper function to get the colour coded label of a US EPA index
+///
+/// An index of 0 means the provider did not report one.
+fn epa_category(index: i64) -> String {
+    if index < 1 {
+        return t("air_quality.unknown", &[]);
+    }
+    let color = match index {
+        1 => "32",
+        2 => "33",
+        3 => "38;5;208",
+        4 => "31",
+        5 => "35",
+        _ => "38;5;88",
+    };
+    colorize(&t(&format!("air_quality.epa.{}", index.min(6)), &[]), color)
+}
+
+/// Helper function to get the colour coded label of a UK DEFRA index
+///
+/// An index of 0 means the provider did not report one.
+fn defra_category(index: i64) -> String {
+    let (band, color) = match index {
+        ..=0 => return t("air_quality.unknown", &[]),
+        1..=3 => ("low", "32"),
+        4..=6 => ("moderate", "33"),
+        7..=9 => ("high", "31"),
+        _ => ("very_high", "35"),
+    };
+    colorize(&t(&format!("air_quality.defra.{}", band), &[]), color)
+}
+
+/// Helper function to colour a text with an ANSI colour code
+///
+/// The text stays plain if stdout is not a terminal or `NO_COLOR` is set.
+fn colorize(text: &str, color: &str) -> String {
+    if std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none() {
+        format!("\x1b[{}m{}\x1b[0m", color, text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Helper function to count the visible characters of a text, skipping
+/// ANSI escape sequences
+fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut in_escape = false;
+    for c in text.chars() {
+        match (in_escape, c) {
+            (false, '\x1b') => in_escape = true,
+            (false, _) => width += 1,
+            (true, 'm') => in_escape = false,
+            (true, _) => {}
+        }
+    }
+    width
+}
+
+/// Helper function to lay out the cells of an hourly table row
+///
+/// # Arguments
+///
+/// * `cells` - The cells of the row, the last one takes the remaining space
+fn table_row(cells: &[String; 7]) -> String {
+    let mut columns = HOURLY_COLUMNS;
+    if clock_format() == ClockFormat::H12 {
+        columns[0] = HOURLY_TIME_COLUMN_12H;
+    }
+
+    let mut row = String::new();
+    for (cell, column_width) in cells.iter().zip(columns) {
+        let cell: String = cell.chars().take(column_width - 1).collect();
+        row.push_str(&format!("{:<column_width$}", cell));
+    }
+    let remaining = PRINT_WIDTH - 2 - columns.iter().sum::<usize>();
+    row.extend(cells[6].chars().take(remaining));
+    row
+}
+
 /// Helper function to print a line
 ///
 /// # Arguments
@@ -581,11 +1351,21 @@ impl ForecastWeather {
 /// * `line_type` - A line type for the output
 fn print_line(key: &str, content: &[(&str, &str)], width: usize, line_type: LineType) {
     let text = match line_type {
-        LineType::Header => Lingua::t(key, content).unwrap().to_uppercase(),
-        LineType::Text => Lingua::t(key, content).unwrap(),
+        LineType::Header => t(key, content).to_uppercase(),
+        LineType::Text => t(key, content),
     };
+    print_text(&text, width);
+}
+
+/// Helper function to print an already translated text as a line
+///
+/// # Arguments
+///
+/// * `text` - A string slice that holds the text to print
+/// * `width` - A width to calculate the padding
+fn print_text(text: &str, width: usize) {
     let formatted = format!("| {}", text);
-    let padding = width - formatted.chars().count();
+    let padding = width.saturating_sub(visible_width(&formatted));
     println!("{}{} │", formatted, " ".repeat(padding));
 }
 
@@ -613,3 +1393,140 @@ fn print_separator(width: usize, style: char) {
         }
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    fn window(from: Option<u32>, to: Option<u32>) -> Vec<u32> {
+        (0..24)
+            .filter(|&hour| is_in_window(at(hour), from.map(at), to.map(at)))
+            .collect()
+    }
+
+    fn search_location(region: &str, country: &str) -> SearchLocation {
+        SearchLocation {
+            id: 2950159,
+            name: "Berlin".to_string(),
+            region: region.to_string(),
+            country: country.to_string(),
+            lat: 52.52437,
+            lon: 13.41053,
+        }
+    }
+
+    #[test]
+    fn display_name_leaves_out_empty_parts() {
+        assert_eq!(
+            search_location("Land Berlin", "Germany").display_name(),
+            "Berlin, Land Berlin, Germany"
+        );
+        assert_eq!(
+            search_location("", "Germany").display_name(),
+            "Berlin, Germany"
+        );
+        assert_eq!(search_location("", "").display_name(), "Berlin");
+    }
+
+    fn alert(headline: &str, severity: &str, effective: &str) -> Alert {
+        Alert {
+            headline: headline.to_string(),
+            event: String::new(),
+            severity: severity.to_string(),
+            urgency: String::new(),
+            areas: String::new(),
+            effective: effective.to_string(),
+            expires: String::new(),
+            description: String::new(),
+        }
+    }
+
+    #[test]
+    fn alerts_are_sorted_by_severity_and_time() {
+        let mut alerts = vec![
+            alert("minor", "Minor", "2026-10-18T08:00:00+02:00"),
+            alert("unknown", "Unknown", "2026-10-18T06:00:00+02:00"),
+            alert("severe late", "Severe", "2026-10-18T16:00:00Z"),
+            alert("severe no time", "Severe", ""),
+            alert("extreme", "extreme", "2026-10-19T00:00:00+02:00"),
+            // Earlier than "severe late" once both are in UTC
+            alert("severe early", "Severe", "2026-10-18T17:00:00+02:00"),
+            alert("moderate", "Moderate", "2026-10-18T12:00:00+02:00"),
+        ];
+        sort_alerts(&mut alerts);
+
+        let headlines: Vec<&str> = alerts.iter().map(|alert| alert.headline.as_str()).collect();
+        assert_eq!(
+            headlines,
+            [
+                "extreme",
+                "severe early",
+                "severe late",
+                "severe no time",
+                "moderate",
+                "minor",
+                "unknown",
+            ]
+        );
+    }
+
+    #[test]
+    fn window_within_a_day() {
+        assert_eq!(window(Some(8), Some(11)), [8, 9, 10, 11]);
+        assert_eq!(window(Some(21), None), [21, 22, 23]);
+        assert_eq!(window(None, Some(2)), [0, 1, 2]);
+        assert_eq!(window(Some(5), Some(5)), [5]);
+        assert_eq!(window(None, None).len(), 24);
+    }
+
+    #[test]
+    fn window_wrapping_past_midnight() {
+        assert_eq!(window(Some(22), Some(2)), [0, 1, 2, 22, 23]);
+        assert_eq!(window(Some(23), Some(0)), [0, 23]);
+    }
+
+    #[test]
+    fn missing_air_quality_indices_are_unknown() {
+        let unknown = t("air_quality.unknown", &[]);
+
+        assert_eq!(epa_category(0), unknown);
+        assert_eq!(defra_category(0), unknown);
+        assert!(epa_category(1).contains(&t("air_quality.epa.1", &[])));
+        assert!(epa_category(7).contains(&t("air_quality.epa.6", &[])));
+        assert!(defra_category(1).contains(&t("air_quality.defra.low", &[])));
+    }
+
+    fn location_at(localtime: &str, utc: &str) -> WeatherLocation {
+        WeatherLocation {
+            name: "Berlin".to_string(),
+            region: String::new(),
+            country: String::new(),
+            lat: 0.0,
+            lon: 0.0,
+            tz_id: String::new(),
+            localtime_epoch: utc.parse::<DateTime<Utc>>().unwrap().timestamp(),
+            localtime: localtime.to_string(),
+        }
+    }
+
+    #[test]
+    fn date_at_the_location_uses_its_offset() {
+        let evening = "2026-10-17T23:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let date = |day| NaiveDate::from_ymd_opt(2026, 10, day).unwrap();
+
+        // UTC+2, fetched a few seconds after the local minute started
+        let berlin = location_at("2026-10-18 00:30", "2026-10-17T22:30:42Z");
+        assert_eq!(berlin.date_at(evening), Some(date(18)));
+        // UTC-5
+        let new_york = location_at("2026-10-17 19:30", "2026-10-18T00:30:05Z");
+        assert_eq!(new_york.date_at(evening), Some(date(17)));
+        assert_eq!(
+            location_at("", "2026-10-18T00:30:05Z").date_at(evening),
+            None
+        );
+    }
+}
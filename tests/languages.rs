@@ -0,0 +1,144 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use chrono::NaiveDate;
+use lingua_i18n_rs::prelude::Lingua;
+use rusty_weather::prelude::*;
+
+/// The loaded languages and the active language are global, so the tests
+/// must not run at the same time
+static LANGUAGE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Reset the translations to the embedded ones and activate a language
+fn use_language(lang: &str) -> MutexGuard<'static, ()> {
+    let guard = LANGUAGE_LOCK
+        .lock()
+        .unwrap_or_else(|error| error.into_inner());
+    load_embedded_languages().unwrap();
+    Lingua::set_language(lang).unwrap();
+    guard
+}
+
+fn language_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "rusty-weather-languages-{}-{}",
+        name,
+        std::process::id()
+    ));
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+    for (file, content) in files {
+        fs::write(dir.join(file), content).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn keys_missing_from_a_language_fall_back_to_english() {
+    let _guard = use_language("de");
+    // A German translation that only knows a single text
+    Lingua::load_translations_from_str("de", r#"{"astronomy": {"sun": "Sonne"}}"#).unwrap();
+
+    assert_eq!(translate("astronomy.sun", &[]).as_deref(), Some("Sonne"));
+    assert_eq!(translate("astronomy.moon", &[]).as_deref(), Some("Moon"));
+    assert_eq!(
+        translate("search.title", &[("query", "Berlin")]).as_deref(),
+        Some("Locations matching \"Berlin\"")
+    );
+    assert_eq!(translate("no.such.key", &[]), None);
+    assert_eq!(t("no.such.key", &[]), "no.such.key");
+
+    load_embedded_languages().unwrap();
+}
+
+#[test]
+fn external_files_override_single_texts() {
+    let _guard = use_language("de");
+    let dir = language_dir(
+        "override",
+        &[("de.json", r#"{"astronomy": {"sun": "Die Sonne"}}"#)],
+    );
+
+    assert!(load_language_dir(&dir).is_empty());
+    assert_eq!(
+        translate("astronomy.sun", &[]).as_deref(),
+        Some("Die Sonne")
+    );
+    // All other texts of the embedded translation are kept
+    assert_eq!(translate("astronomy.moon", &[]).as_deref(), Some("Mond"));
+
+    load_embedded_languages().unwrap();
+}
+
+#[test]
+fn broken_files_are_reported_and_skipped() {
+    let _guard = use_language("en");
+    let dir = language_dir("broken", &[("de.json", "{"), ("notes.txt", "{")]);
+
+    let errors = load_language_dir(&dir);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], LanguageError::Parse { .. }));
+    assert!(load_language_dir(&dir.join("missing")).is_empty());
+}
+
+#[test]
+fn embedded_translations_are_complete() {
+    let _guard = use_language("en");
+
+    let reports = check_languages();
+    let german = reports
+        .iter()
+        .find(|report| report.language == "de")
+        .unwrap();
+    assert_eq!(german.problem_count(), 0, "{:?}", german);
+}
+
+#[test]
+fn check_reports_missing_keys_and_placeholders() {
+    let _guard = use_language("en");
+    let dir = language_dir(
+        "check",
+        &[(
+            "xx.json",
+            r#"{"current": {"weather_report": "Weather for {{town}}"}, "bogus": "Extra"}"#,
+        )],
+    );
+    assert!(load_language_dir(&dir).is_empty());
+
+    let reports = check_languages();
+    let report = reports
+        .iter()
+        .find(|report| report.language == "xx")
+        .unwrap();
+    assert!(report.missing.contains(&"astronomy.sun".to_string()));
+    assert!(
+        !report
+            .missing
+            .contains(&"current.weather_report".to_string())
+    );
+    assert_eq!(report.extra, ["bogus"]);
+    assert_eq!(report.placeholder_mismatches.len(), 1);
+    let mismatch = &report.placeholder_mismatches[0];
+    assert_eq!(mismatch.key, "current.weather_report");
+    assert_eq!(mismatch.expected, ["city"]);
+    assert_eq!(mismatch.found, ["town"]);
+}
+
+#[test]
+fn dates_and_numbers_follow_the_language() {
+    let _guard = use_language("de");
+    let sunday = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+
+    assert_eq!(format_day(sunday), "Sonntag, 18.10.2026");
+    assert_eq!(format_date(sunday), "18.10.2026");
+    assert_eq!(format_number(12.3), "12,3");
+
+    set_format_overrides(FormatConfig {
+        day: Some("%a %d".to_string()),
+        ..Default::default()
+    });
+    let short = format_day(sunday);
+    set_format_overrides(FormatConfig::default());
+    assert_eq!(short, "So 18");
+}
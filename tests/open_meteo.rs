@@ -0,0 +1,171 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use chrono::NaiveDate;
+use rusty_weather::prelude::*;
+
+/// Serve the fixtures like the Open-Meteo endpoints and record the request
+/// lines
+fn start_stub() -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&requests);
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut header = String::new();
+            while reader.read_line(&mut header).unwrap() > 2 {
+                header.clear();
+            }
+
+            let path = request_line.split(' ').nth(1).unwrap_or("").to_string();
+            let body = match path.split('?').next().unwrap_or("") {
+                "/search" => include_str!("fixtures/open_meteo/geocoding.json"),
+                "/forecast" => include_str!("fixtures/open_meteo/forecast.json"),
+                "/archive" => include_str!("fixtures/open_meteo/archive.json"),
+                "/air-quality" => include_str!("fixtures/open_meteo/air_quality.json"),
+                _ => "",
+            };
+            recorded.lock().unwrap().push(path);
+
+            let status = if body.is_empty() {
+                "404 Not Found"
+            } else {
+                "200 OK"
+            };
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    (url, requests)
+}
+
+fn provider(base_url: &str) -> Provider {
+    let client = HttpClient::new(HttpConfig {
+        max_retries: 0,
+        base_url: Some(base_url.to_string()),
+        ..Default::default()
+    })
+    .unwrap();
+    Provider::new(ProviderKind::OpenMeteo, client)
+}
+
+fn berlin() -> LocationQuery {
+    LocationQuery::City("Berlin".to_string())
+}
+
+#[tokio::test]
+async fn current_weather_resolves_the_city_and_adds_air_quality() {
+    let (url, requests) = start_stub();
+    let options = FetchOptions {
+        air_quality: true,
+        alerts: false,
+    };
+
+    let weather = provider(&url)
+        .current(&berlin(), "en", &options)
+        .await
+        .unwrap();
+
+    assert_eq!(weather.location.name, "Berlin");
+    assert_eq!(weather.location.country, "Germany");
+    assert_eq!(weather.location.tz_id, "Europe/Berlin");
+    assert_eq!(weather.current.temp_c, 17.0);
+    assert_eq!(weather.current.wind_dir, "SSW");
+    assert_eq!(weather.current.air_quality.unwrap().us_epa_index, 1);
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 3);
+    assert!(requests[0].starts_with("/search?name=Berlin&count=1"));
+    assert!(requests[1].starts_with("/forecast?latitude=52.52437&longitude=13.41053"));
+    assert!(requests[2].starts_with("/air-quality?"));
+}
+
+#[tokio::test]
+async fn forecast_has_daily_air_quality() {
+    let (url, _) = start_stub();
+    let options = FetchOptions {
+        air_quality: true,
+        alerts: false,
+    };
+
+    let forecast = provider(&url)
+        .forecast(&berlin(), 1, "en", &options)
+        .await
+        .unwrap();
+
+    let days = &forecast.forecast.forecastday;
+    assert_eq!(days.len(), 1);
+    assert_eq!(days[0].hour.len(), 3);
+    assert_eq!(days[0].astro.sunrise, "07:31");
+    assert_eq!(days[0].day.air_quality.as_ref().unwrap().gb_defra_index, 4);
+}
+
+#[tokio::test]
+async fn forecast_alerts_are_not_supported() {
+    let (url, requests) = start_stub();
+    let options = FetchOptions {
+        air_quality: false,
+        alerts: true,
+    };
+
+    let result = provider(&url).forecast(&berlin(), 1, "en", &options).await;
+
+    assert!(matches!(result, Err(WeatherError::UnsupportedFeature(_))));
+    assert!(requests.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn history_uses_the_archive() {
+    let (url, requests) = start_stub();
+    let date = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+
+    let history = provider(&url).history(&berlin(), date, "en").await.unwrap();
+
+    assert_eq!(history.forecast.forecastday[0].date, "2026-10-18");
+    let requests = requests.lock().unwrap();
+    assert!(requests[1].starts_with("/archive?"));
+    assert!(requests[1].contains("start_date=2026-10-18&end_date=2026-10-18"));
+}
+
+#[tokio::test]
+async fn search_returns_every_match() {
+    let (url, requests) = start_stub();
+
+    let locations = provider(&url).search("Berlin", "en").await.unwrap();
+
+    assert_eq!(locations.len(), 3);
+    assert_eq!(locations[1].region, "New Hampshire");
+    assert!(requests.lock().unwrap()[0].contains("count=10"));
+}
+
+#[tokio::test]
+async fn coordinates_are_not_geocoded() {
+    let (url, requests) = start_stub();
+    let query = LocationQuery::Coordinates {
+        lat: 52.52,
+        lon: 13.42,
+    };
+
+    provider(&url)
+        .current(&query, "en", &FetchOptions::default())
+        .await
+        .unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].starts_with("/forecast?latitude=52.52&longitude=13.42"));
+}